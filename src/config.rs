@@ -1,54 +1,301 @@
-use log::debug;
-use std::{env, fs};
+use log::{debug, error, warn};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::{env, fs, process};
 
 use serde::{Deserialize, Serialize};
 use simplelog::info;
 use toml::{value::Table, Value};
+use toml_edit::{DocumentMut, Item};
 
 const CONFIG_FILENAME: &str = "config.toml";
+const CONFIG_DIR: &str = "timeular-reader";
+const CONFIG_ENV: &str = "TIMEULAR_READER_CONFIG";
 
-static mut CONFIG_PATH: String = String::new();
-pub trait Config<'de>: Serialize + Deserialize<'de> + Default {}
+pub mod diagnostic;
+pub mod file;
+pub mod interpolate;
+pub mod migration;
+pub mod profile;
+pub mod prompt;
+pub mod secret;
+pub mod watcher;
+pub mod wizard;
+
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+/// Config values as handed out by `get_config`, to tell resolved values apart from changed ones
+static RESOLVED: Mutex<BTreeMap<String, Value>> = Mutex::new(BTreeMap::new());
+pub trait Config<'de>: Serialize + Deserialize<'de> + Default {
+    /// Fields holding passwords, tokens etc. they are kept in the system keyring when possible
+    fn secret_fields() -> &'static [&'static str] {
+        &[]
+    }
+}
 
 pub fn get_config<'de, T: Config<'de>>(key: &str) -> T {
+    match try_get_config::<T>(key) {
+        Ok(Some(config)) => config,
+        Ok(None) => initialize_default_config_key::<T>(key),
+        Err(e) => {
+            error!("Invalid config: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Same as `get_config` but returns the error instead of exiting, and `None` if `key` is missing
+pub fn try_get_config<'de, T: Config<'de>>(key: &str) -> Result<Option<T>, String> {
     ensure_file_exists();
 
-    let config: Value = toml::from_str(&fs::read_to_string(get_config_path()).unwrap()).unwrap();
-    let value = config.get(&key);
+    let contents = fs::read_to_string(get_config_path()).map_err(|e| e.to_string())?;
+    let config: Value = toml::from_str(&contents).map_err(|e| e.to_string())?;
 
-    if value.is_none() {
-        return initialize_default_config_key::<T>(key);
-    }
+    let read_path = profile::read_path(&config, key);
+    let path = read_path.join(".");
+    let mut value = match profile::get(&config, &read_path) {
+        Some(value) => value.to_owned(),
+        None => return Ok(None),
+    };
+    interpolate::resolve(&path, &mut value, T::secret_fields())?;
+    resolve_secrets::<T>(&path, &mut value)?;
+
+    let config = value
+        .clone()
+        .try_into::<T>()
+        .map_err(|e| format!("[{}] {}", path, e))?;
 
-    value.unwrap().to_owned().try_into::<T>().unwrap()
+    RESOLVED.lock().unwrap().insert(path, value);
+
+    Ok(Some(config))
 }
 
 pub fn update_config<'de, T: Config<'de>>(key: &str, config: &T) {
     ensure_file_exists();
 
-    let mut whole_config: Table =
-        toml::from_str(&fs::read_to_string(get_config_path()).unwrap()).unwrap();
+    let path = get_config_path();
+    let _lock = file::lock(path);
+    let mut document = file::read(path);
+    let whole_config: Value = toml::from_str(&document.to_string()).unwrap();
+    let read_path = profile::read_path(&whole_config, key);
+    let write_path = profile::write_path(&whole_config, key);
 
-    whole_config.insert(key.to_string(), Value::try_from(config).unwrap());
+    let mut value = Value::try_from(config).unwrap();
+    let mut resolved = RESOLVED.lock().unwrap();
+    if let (Some(raw), Some(resolved)) = (
+        profile::get(&whole_config, &read_path),
+        resolved.get(&read_path.join(".")),
+    ) {
+        interpolate::restore(&mut value, raw, resolved, T::secret_fields());
+    }
+    resolved.insert(write_path.join("."), Value::try_from(config).unwrap());
+    drop(resolved);
 
-    save_config_file(&whole_config);
+    store_secrets::<T>(&write_path.join("."), &mut value);
+
+    let mut new_config = Table::new();
+    new_config.insert(key.to_string(), value);
+    let mut new_document: DocumentMut = toml::to_string(&new_config).unwrap().parse().unwrap();
+
+    file::set_section(
+        &mut document,
+        &write_path,
+        new_document.remove(key).unwrap(),
+    );
+    file::write(path, &document);
     info!("Config updated");
 }
 
-fn get_config_path() -> String {
-    unsafe {
-        if CONFIG_PATH.is_empty() {
-            let mut path = env::current_exe()
-                .expect("Executable should have a path")
-                .parent()
-                .unwrap()
-                .to_path_buf();
-            path.push(CONFIG_FILENAME);
-            CONFIG_PATH = path.to_str().unwrap().to_string();
-            debug!("config path: \"{}\"", CONFIG_PATH);
+/// Moves plaintext secrets of the config under `key` into the keyring, in every profile
+pub fn migrate_secrets<'de, T: Config<'de>>(key: &str) {
+    ensure_file_exists();
+
+    let path = get_config_path();
+    let _lock = file::lock(path);
+    let mut document = file::read(path);
+    let whole_config: Value = toml::from_str(&document.to_string()).unwrap();
+
+    let section_paths = [vec![key.to_string()]].into_iter().chain(
+        profile::list_profiles(&whole_config)
+            .into_iter()
+            .map(|name| vec![profile::PROFILES_KEY.to_string(), name, key.to_string()]),
+    );
+
+    let mut migrated = false;
+    for section_path in section_paths {
+        let section = match section_path
+            .iter()
+            .try_fold(document.as_item_mut(), |item, key| item.get_mut(key))
+        {
+            Some(section) => section,
+            None => continue,
+        };
+
+        for field in T::secret_fields() {
+            let secret = match section.get_mut(field).and_then(Item::as_value_mut) {
+                Some(secret) => secret,
+                None => continue,
+            };
+            let plaintext = match secret.as_str() {
+                Some(s)
+                    if !s.is_empty()
+                        && !secret::is_reference(s)
+                        && !interpolate::is_template(s) =>
+                {
+                    s
+                }
+                _ => continue,
+            };
+
+            let account = format!("{}.{}", section_path.join("."), field);
+            let reference = match secret::store(&account, plaintext) {
+                Some(reference) => reference,
+                // No keyring, the secrets stay where they are
+                None => return,
+            };
+            let decor = secret.decor().clone();
+            *secret = reference.into();
+            *secret.decor_mut() = decor;
+
+            info!("Moved {} to the keyring", account);
+            migrated = true;
         }
+    }
 
-        CONFIG_PATH.clone()
+    if migrated {
+        file::write(path, &document);
+    }
+}
+
+/// Fails instead of leaving the field empty, an empty secret would be asked for again
+fn resolve_secrets<'de, T: Config<'de>>(path: &str, value: &mut Value) -> Result<(), String> {
+    for field in T::secret_fields() {
+        if let Some(Value::String(reference)) = value.get_mut(field) {
+            if secret::is_reference(reference) {
+                *reference = secret::resolve(reference).map_err(|e| {
+                    format!(
+                        "{}.{}: {}, unlock the keyring or replace the reference with the value",
+                        path, field, e
+                    )
+                })?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn store_secrets<'de, T: Config<'de>>(key: &str, value: &mut Value) {
+    for field in T::secret_fields() {
+        if let Some(Value::String(secret)) = value.get_mut(field) {
+            if secret.is_empty() || secret::is_reference(secret) || interpolate::is_template(secret)
+            {
+                continue;
+            }
+            if let Some(reference) = secret::store(&format!("{}.{}", key, field), secret) {
+                *secret = reference;
+            }
+        }
+    }
+}
+
+pub fn expand_path(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(mut home) = dirs::home_dir() {
+            home.push(rest);
+            return home;
+        }
+    }
+
+    PathBuf::from(path)
+}
+
+/// Resolves the config file from `path` (the `--config` flag), `TIMEULAR_READER_CONFIG`
+/// or the user config dir, in that order. Has to be called before the config is first read.
+pub fn init_config_path(path: Option<PathBuf>) {
+    let path = match path {
+        Some(path) => expand_path(&path.to_string_lossy()),
+        None => match env::var(CONFIG_ENV) {
+            Ok(path) if !path.is_empty() => expand_path(&path),
+            _ => default_config_path(),
+        },
+    };
+
+    if CONFIG_PATH.set(path).is_err() {
+        error!("Config path is already set to {:?}", get_config_path());
+    }
+}
+
+pub fn get_config_path() -> &'static Path {
+    CONFIG_PATH.get_or_init(default_config_path)
+}
+
+fn default_config_path() -> PathBuf {
+    let mut path = match user_config_dir() {
+        Some(path) => path,
+        None => {
+            let path = legacy_config_path().unwrap_or_else(|| PathBuf::from(CONFIG_FILENAME));
+            warn!(
+                "Could not find the user config directory, set XDG_CONFIG_HOME or HOME, using {}",
+                path.display()
+            );
+            return path;
+        }
+    };
+    path.push(CONFIG_DIR);
+    path.push(CONFIG_FILENAME);
+
+    migrate_legacy_config(&path);
+
+    path
+}
+
+/// `$XDG_CONFIG_HOME` on every platform, otherwise the platform's config directory
+fn user_config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(dirs::config_dir)
+}
+
+fn legacy_config_path() -> Option<PathBuf> {
+    env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name(CONFIG_FILENAME))
+}
+
+/// Older versions kept the config next to the executable, copy it to the new location
+fn migrate_legacy_config(path: &Path) {
+    if path.exists() {
+        return;
+    }
+
+    let legacy_path = match legacy_config_path() {
+        Some(legacy_path) => legacy_path,
+        None => return,
+    };
+
+    if !legacy_path.is_file() {
+        return;
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+
+    match fs::copy(&legacy_path, path) {
+        Ok(_) => info!(
+            "Config copied from {} to {}, the old file is no longer used and can be removed",
+            legacy_path.display(),
+            path.display()
+        ),
+        Err(e) => error!(
+            "Could not copy config from {}: {}",
+            legacy_path.display(),
+            e
+        ),
     }
 }
 
@@ -60,12 +307,96 @@ fn initialize_default_config_key<'de, T: Config<'de>>(key: &str) -> T {
     def_config
 }
 
-fn save_config_file<T: ?Sized + Serialize>(contents: &T) {
-    fs::write(get_config_path(), toml::to_string(&contents).unwrap()).unwrap();
+pub(crate) fn ensure_file_exists() {
+    let path = get_config_path();
+    if fs::metadata(path).is_err() {
+        debug!("creating config file \"{}\"", path.display());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        // Another process may have created it in the meantime
+        if let Ok(mut file) = OpenOptions::new().create_new(true).write(true).open(path) {
+            writeln!(file, "version = {}", migration::CONFIG_VERSION).unwrap();
+        }
+    }
 }
 
-fn ensure_file_exists() {
-    if fs::metadata(get_config_path()).is_err() {
-        fs::write(get_config_path(), "").unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::toggl::config::TogglConfig;
+
+    #[derive(Serialize, Deserialize, Default)]
+    struct SecretConfig {
+        password: String,
+    }
+
+    impl<'de> Config<'de> for SecretConfig {
+        fn secret_fields() -> &'static [&'static str] {
+            &["password"]
+        }
+    }
+
+    /// The config path can only be set once, tests using the config file share it one at a time
+    static CONFIG_FILE: Mutex<()> = Mutex::new(());
+
+    fn with_config_file(contents: &str, test: impl FnOnce(&Path)) {
+        let _guard = CONFIG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+        let path = env::temp_dir().join(format!("timeular-config-{}.toml", process::id()));
+        if CONFIG_PATH.get().is_none() {
+            init_config_path(Some(path.clone()));
+        }
+        fs::write(&path, contents).unwrap();
+
+        test(&path);
+
+        for extension in ["", ".bak", ".lock", ".v0.bak"] {
+            let _ = fs::remove_file(format!("{}{}", path.display(), extension));
+        }
+    }
+
+    #[test]
+    fn secrets_of_every_profile_are_migrated() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        with_config_file(
+            "version = 1\n\
+             active_profile = \"work\"\n\n\
+             [secret]\npassword = \"a\"\n\n\
+             [profiles.work.secret]\npassword = \"b\" # work\n\n\
+             [profiles.home.secret]\npassword = \"${HOME_PASSWORD}\"\n",
+            |path| {
+                migrate_secrets::<SecretConfig>("secret");
+
+                assert_eq!(
+                    fs::read_to_string(path).unwrap(),
+                    "version = 1\n\
+                     active_profile = \"work\"\n\n\
+                     [secret]\npassword = \"keyring:secret.password\"\n\n\
+                     [profiles.work.secret]\npassword = \"keyring:profiles.work.secret.password\" # work\n\n\
+                     [profiles.home.secret]\npassword = \"${HOME_PASSWORD}\"\n"
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn migrated_project_id_survives_update_config() {
+        with_config_file(
+            "[timeular]\nhandler = \"toggl\"\n\n[toggl]\nworkspace_id = 1\nproject_id = 5\n",
+            |path| {
+                migration::migrate();
+                let mut config = get_config::<TogglConfig>("toggl");
+                config.workspace_id = 2;
+
+                update_config("toggl", &config);
+
+                let config = get_config::<TogglConfig>("toggl");
+                assert_eq!(config.workspace_id, 2);
+                assert_eq!(config.project_id, Some(5));
+                assert!(fs::read_to_string(path)
+                    .unwrap()
+                    .contains("project_id = 5\n"));
+            },
+        );
     }
 }
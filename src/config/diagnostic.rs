@@ -0,0 +1,248 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
+
+use serde::de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::Deserializer;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in the config file, `span` is the byte range it points to
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Option<Range<usize>>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Option<Range<usize>>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Option<Range<usize>>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Formats the message with the line and column of the span and the line it points to
+    pub fn render(&self, path: &Path, contents: &str) -> String {
+        let mut rendered = self.message.clone();
+
+        let span = match &self.span {
+            Some(span) => span,
+            None => {
+                rendered.push_str(format!("\n --> {}", path.display()).as_str());
+                return rendered;
+            }
+        };
+
+        let start = span.start.min(contents.len());
+        let line_start = contents[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = contents[start..]
+            .find('\n')
+            .map_or(contents.len(), |i| start + i);
+        let line_num = contents[..start].matches('\n').count() + 1;
+        let column = contents[line_start..start].chars().count() + 1;
+        let width = contents[start..span.end.clamp(start, line_end)]
+            .chars()
+            .count()
+            .max(1);
+        let gutter = " ".repeat(line_num.to_string().len());
+
+        rendered.push_str(
+            format!(
+                "\n{gutter}--> {}:{}:{}\n{gutter} |\n{} | {}\n{gutter} | {}{}",
+                path.display(),
+                line_num,
+                column,
+                line_num,
+                contents[line_start..line_end].trim_end_matches('\r'),
+                " ".repeat(column - 1),
+                "^".repeat(width)
+            )
+            .as_str(),
+        );
+
+        rendered
+    }
+}
+
+/// Deserializes the section at `path` of the document as `T`, so the error points into the file
+pub fn check_section<T: DeserializeOwned>(contents: &str, path: &[&str]) -> Option<Diagnostic> {
+    let section = Section::<T> {
+        path,
+        section: PhantomData,
+    };
+
+    section
+        .deserialize(toml::Deserializer::new(contents))
+        .err()
+        .map(|e| Diagnostic::error(format!("[{}] {}", path.join("."), e.message()), e.span()))
+}
+
+/// Returns the candidate closest to `name`, if it is close enough to be a typo
+pub fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2.max(name.len() / 3))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if a == *b {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j + 1])
+            };
+            prev = current;
+        }
+    }
+
+    row[b.len()]
+}
+
+struct Section<'a, T> {
+    path: &'a [&'a str],
+    section: PhantomData<T>,
+}
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for Section<'_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for Section<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a table")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let (first, rest) = self
+            .path
+            .split_first()
+            .expect("Section path should not be empty");
+
+        while let Some(key) = map.next_key::<String>()? {
+            if key != *first {
+                map.next_value::<IgnoredAny>()?;
+            } else if rest.is_empty() {
+                map.next_value::<T>()?;
+            } else {
+                map.next_value_seed(Section::<T> {
+                    path: rest,
+                    section: PhantomData,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Csv {
+        path: String,
+    }
+
+    #[test]
+    fn render_points_at_the_span() {
+        let contents = "[csv]\npath = 5\n";
+        let diagnostic = Diagnostic::error("invalid type", Some(13..14));
+
+        assert_eq!(
+            diagnostic.render(Path::new("config.toml"), contents),
+            "invalid type\n --> config.toml:2:8\n  |\n2 | path = 5\n  |        ^"
+        );
+    }
+
+    #[test]
+    fn render_without_span_names_the_file() {
+        let diagnostic = Diagnostic::warning("no handler is set", None);
+
+        assert_eq!(
+            diagnostic.render(Path::new("config.toml"), ""),
+            "no handler is set\n --> config.toml"
+        );
+    }
+
+    #[test]
+    fn render_widens_the_gutter_for_long_files() {
+        let contents = format!("{}handler = \"togl\"\n", "\n".repeat(11));
+        let start = contents.find('"').unwrap();
+        let diagnostic = Diagnostic::error("unknown handler", Some(start..start + 6));
+
+        assert_eq!(
+            diagnostic.render(Path::new("c.toml"), &contents),
+            "unknown handler\n  --> c.toml:12:11\n   |\n12 | handler = \"togl\"\n   |           ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn suggest_close_names_only() {
+        let handlers = ["toggl", "clockify", "hackaru", "csv"];
+
+        assert_eq!(suggest("togl", handlers), Some("toggl"));
+        assert_eq!(suggest("clokcify", handlers), Some("clockify"));
+        assert_eq!(suggest("redmine", handlers), None);
+    }
+
+    #[test]
+    fn check_section_reports_nested_errors() {
+        let contents = "[profiles.work.csv]\npath = 5\n";
+
+        let diagnostic = check_section::<Csv>(contents, &["profiles", "work", "csv"]).unwrap();
+
+        assert!(diagnostic.is_error());
+        assert!(diagnostic
+            .message
+            .starts_with("[profiles.work.csv] invalid type"));
+        assert_eq!(&contents[diagnostic.span.unwrap()], "5");
+    }
+
+    #[test]
+    fn check_section_accepts_valid_section() {
+        let contents = "[csv]\npath = \"a.csv\"\n";
+
+        assert!(check_section::<Csv>(contents, &["csv"]).is_none());
+    }
+}
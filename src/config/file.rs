@@ -0,0 +1,320 @@
+use std::fs::{self, File, OpenOptions};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::debug;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// Hash of the contents this process wrote last, to tell its own writes apart from the user's
+static WRITTEN: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Holds the advisory lock on `config.toml.lock` until dropped
+pub struct ConfigLock {
+    file: File,
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// Blocks until no other process is writing the config
+pub fn lock(path: &Path) -> ConfigLock {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(with_extension(path, "lock"))
+        .expect("Config lock file should be writable");
+    file.lock().expect("Config lock should be acquired");
+
+    ConfigLock { file }
+}
+
+pub fn read(path: &Path) -> DocumentMut {
+    fs::read_to_string(path)
+        .unwrap()
+        .parse::<DocumentMut>()
+        .unwrap()
+}
+
+/// Writes the config to a temp file and renames it over the old one, so the config is never
+/// left half written, the previous version is kept as `config.toml.bak`
+pub fn write(path: &Path, document: &DocumentMut) {
+    let contents = document.to_string();
+    let tmp_path = with_extension(path, "tmp");
+    let mut tmp = File::create(&tmp_path).unwrap();
+    tmp.write_all(contents.as_bytes()).unwrap();
+    tmp.sync_all().unwrap();
+
+    if let Ok(metadata) = fs::metadata(path) {
+        fs::set_permissions(&tmp_path, metadata.permissions()).unwrap();
+        if metadata.len() > 0 {
+            fs::copy(path, with_extension(path, "bak")).unwrap();
+        }
+    }
+
+    *WRITTEN.lock().unwrap() = Some(hash(&contents));
+    fs::rename(&tmp_path, path).unwrap();
+    debug!("config written to \"{}\"", path.display());
+}
+
+/// Whether `contents` is what this process wrote last
+pub fn is_own_write(contents: &str) -> bool {
+    *WRITTEN.lock().unwrap() == Some(hash(contents))
+}
+
+fn hash(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Sets the section at `path` to `new`, keeping comments and formatting of everything that
+/// didn't change, missing parent tables are created
+pub fn set_section(document: &mut DocumentMut, path: &[String], new: Item) {
+    let (key, parents) = path.split_last().expect("Section path should not be empty");
+
+    let mut table = document.as_table_mut();
+    for parent in parents {
+        table = table
+            .entry(parent)
+            .or_insert_with(|| {
+                let mut table = Table::new();
+                table.set_implicit(true);
+                Item::Table(table)
+            })
+            .as_table_mut()
+            .expect("Parent of a config section should be a table");
+    }
+
+    match table.get_mut(key) {
+        Some(existing) => merge(existing, new),
+        None => {
+            table.insert(key, detach(new));
+        }
+    }
+}
+
+fn merge(existing: &mut Item, new: Item) {
+    match (existing, new) {
+        (Item::Table(existing), Item::Table(new)) => {
+            let removed: Vec<String> = existing
+                .iter()
+                .map(|(key, _)| key.to_string())
+                .filter(|key| !new.contains_key(key))
+                .collect();
+            for key in removed {
+                existing.remove(&key);
+            }
+
+            for (key, item) in new {
+                match existing.get_mut(&key) {
+                    Some(existing) if same_kind(existing, &item) => merge(existing, item),
+                    // Reinserted, the formatting of a value doesn't fit a table
+                    _ => {
+                        existing.remove(&key);
+                        existing.insert(&key, detach(item));
+                    }
+                }
+            }
+        }
+        (Item::ArrayOfTables(existing), Item::ArrayOfTables(new))
+            if existing.len() == new.len() =>
+        {
+            for (existing, new) in existing.iter_mut().zip(new) {
+                let mut item = Item::Table(std::mem::take(existing));
+                merge(&mut item, Item::Table(new));
+                *existing = item.into_table().unwrap();
+            }
+        }
+        (Item::Value(existing), Item::Value(new)) => {
+            if !same_value(existing, &new) {
+                let decor = existing.decor().clone();
+                *existing = new;
+                *existing.decor_mut() = decor;
+            }
+        }
+        (existing, new) => *existing = detach(new),
+    }
+}
+
+fn same_kind(a: &Item, b: &Item) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+/// Drops the positions tables had in the document they were parsed from, so they are written
+/// after their parent table instead of at the top of the file
+fn detach(item: Item) -> Item {
+    match item {
+        Item::Table(table) => Item::Table(detach_table(table)),
+        Item::ArrayOfTables(array) => {
+            Item::ArrayOfTables(array.into_iter().map(detach_table).collect())
+        }
+        item => item,
+    }
+}
+
+fn detach_table(table: Table) -> Table {
+    let mut detached = Table::new();
+    detached.set_implicit(table.is_implicit());
+    for (key, item) in table {
+        detached.insert(&key, detach(item));
+    }
+
+    detached
+}
+
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_str(), b.as_str()) {
+        (Some(a), Some(b)) => a == b,
+        _ => {
+            let mut a = a.clone();
+            let mut b = b.clone();
+            a.decor_mut().clear();
+            b.decor_mut().clear();
+
+            a.to_string() == b.to_string()
+        }
+    }
+}
+
+pub fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(key: &str, toml: &str) -> Item {
+        let mut document = format!("[{}]\n{}", key, toml)
+            .parse::<DocumentMut>()
+            .unwrap();
+
+        document.remove(key).unwrap()
+    }
+
+    fn set(document: &str, path: &[&str], new: Item) -> String {
+        let mut document = document.parse::<DocumentMut>().unwrap();
+        let path: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+
+        set_section(&mut document, &path, new);
+
+        document.to_string()
+    }
+
+    #[test]
+    fn comments_and_formatting_are_kept() {
+        let document = "# my tracker\n[toggl]\n# from the profile page\nworkspace_id = 'abc' # keep\ntimeout = 30\n";
+
+        let result = set(
+            document,
+            &["toggl"],
+            section("toggl", "workspace_id = \"abc\"\ntimeout = 60\n"),
+        );
+
+        assert_eq!(
+            result,
+            "# my tracker\n[toggl]\n# from the profile page\nworkspace_id = 'abc' # keep\ntimeout = 60\n"
+        );
+    }
+
+    #[test]
+    fn removed_keys_are_dropped_and_new_ones_added() {
+        let document = "[csv]\npath = \"a.csv\"\nold = 1\n";
+
+        let result = set(
+            document,
+            &["csv"],
+            section("csv", "path = \"a.csv\"\ndelimiter = \",\"\n"),
+        );
+
+        assert_eq!(result, "[csv]\npath = \"a.csv\"\ndelimiter = \",\"\n");
+    }
+
+    #[test]
+    fn missing_section_is_added_after_the_others() {
+        let document = "version = 1\n\n[timeular]\nhandler = \"csv\"\n";
+
+        let result = set(document, &["csv"], section("csv", "path = \"a.csv\"\n"));
+
+        assert_eq!(
+            result,
+            "version = 1\n\n[timeular]\nhandler = \"csv\"\n\n[csv]\npath = \"a.csv\"\n"
+        );
+    }
+
+    #[test]
+    fn profile_section_creates_implicit_parents() {
+        let document = "[timeular]\nhandler = \"csv\"\n";
+
+        let result = set(
+            document,
+            &["profiles", "work", "csv"],
+            section("csv", "path = \"work.csv\"\n"),
+        );
+
+        assert_eq!(
+            result,
+            "[timeular]\nhandler = \"csv\"\n\n[profiles.work.csv]\npath = \"work.csv\"\n"
+        );
+    }
+
+    #[test]
+    fn empty_array_becomes_array_of_tables_below_its_table() {
+        let document = "[toggl]\nsides = []\nworkspace_id = \"abc\"\n";
+
+        let result = set(
+            document,
+            &["toggl"],
+            section(
+                "toggl",
+                "workspace_id = \"abc\"\n\n[[toggl.sides]]\nside_num = 1\nproject_id = \"p\"\n",
+            ),
+        );
+
+        let toggl = result.find("[toggl]").unwrap();
+        let sides = result.find("[[toggl.sides]]").unwrap();
+        assert!(toggl < sides, "{}", result);
+        assert_eq!(result.matches("sides").count(), 1, "{}", result);
+
+        let value: toml::Value = toml::from_str(&result).unwrap();
+        assert_eq!(value["toggl"]["sides"][0]["project_id"].as_str(), Some("p"));
+    }
+
+    #[test]
+    fn array_of_tables_entries_are_merged() {
+        let document =
+            "[toggl]\n\n# first side\n[[toggl.sides]]\nside_num = 1\nproject_id = \"a\"\n";
+
+        let result = set(
+            document,
+            &["toggl"],
+            section(
+                "toggl",
+                "[[toggl.sides]]\nside_num = 1\nproject_id = \"b\"\n",
+            ),
+        );
+
+        assert_eq!(
+            result,
+            "[toggl]\n\n# first side\n[[toggl.sides]]\nside_num = 1\nproject_id = \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn hash_depends_on_contents_only() {
+        let contents = "[csv]\npath = \"a.csv\"\n";
+
+        assert_eq!(hash(contents), hash(&String::from(contents)));
+        assert_ne!(hash(contents), hash("[csv]\npath = \"b.csv\"\n"));
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use toml::Value;
+
+const COMMAND_SUFFIX: &str = "_command";
+
+/// Command outputs by command, so a secret command runs once and not on every config read
+static COMMAND_OUTPUTS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+pub fn is_template(value: &str) -> bool {
+    value.contains("${")
+}
+
+/// Replaces `${VAR}` with environment variables in all string values and sets every secret
+/// field that has a `<field>_command` sibling to the output of that command
+pub fn resolve(path: &str, value: &mut Value, secret_fields: &[&str]) -> Result<(), String> {
+    resolve_variables(path, value)?;
+
+    let table = match value {
+        Value::Table(table) => table,
+        _ => return Ok(()),
+    };
+
+    for field in secret_fields {
+        let command = match table.get(&format!("{}{}", field, COMMAND_SUFFIX)) {
+            Some(Value::String(command)) if !command.is_empty() => command.clone(),
+            _ => continue,
+        };
+
+        let output = run_command(&command)
+            .map_err(|e| format!("{}.{}{}: {}", path, field, COMMAND_SUFFIX, e))?;
+        table.insert(field.to_string(), Value::String(output));
+    }
+
+    Ok(())
+}
+
+fn resolve_variables(path: &str, value: &mut Value) -> Result<(), String> {
+    match value {
+        Value::String(s) if is_template(s) => {
+            *s = interpolate(s).map_err(|var| {
+                format!(
+                    "Environment variable {} used in {} is not set, to use a literal \"${{\" write \"$${{\"",
+                    var, path
+                )
+            })?;
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter_mut().enumerate() {
+                resolve_variables(&format!("{}[{}]", path, i), value)?;
+            }
+        }
+        Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                resolve_variables(&format!("{}.{}", path, key), value)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Puts back the raw values from the config file wherever `value` still holds what they were
+/// resolved to, so no resolved secret ends up on disk
+pub fn restore(value: &mut Value, raw: &Value, resolved: &Value, secret_fields: &[&str]) {
+    if let (Value::Table(table), Value::Table(raw_table), Value::Table(resolved_table)) =
+        (&mut *value, raw, resolved)
+    {
+        for field in secret_fields {
+            let key = format!("{}{}", field, COMMAND_SUFFIX);
+            let command = match raw_table.get(&key) {
+                Some(command) => command,
+                None => continue,
+            };
+            table.insert(key, command.clone());
+
+            if table.get(*field) == resolved_table.get(*field) {
+                match raw_table.get(*field) {
+                    Some(raw_value) => table.insert(field.to_string(), raw_value.clone()),
+                    None => table.remove(*field),
+                };
+            }
+        }
+    }
+
+    restore_variables(value, raw, resolved);
+}
+
+fn restore_variables(value: &mut Value, raw: &Value, resolved: &Value) {
+    match (value, raw, resolved) {
+        (Value::Table(table), Value::Table(raw), Value::Table(resolved)) => {
+            for (key, value) in table.iter_mut() {
+                if let (Some(raw), Some(resolved)) = (raw.get(key), resolved.get(key)) {
+                    restore_variables(value, raw, resolved);
+                }
+            }
+        }
+        (Value::Array(values), Value::Array(raw), Value::Array(resolved)) => {
+            for ((value, raw), resolved) in values.iter_mut().zip(raw).zip(resolved) {
+                restore_variables(value, raw, resolved);
+            }
+        }
+        (value, raw, resolved) => {
+            if value == resolved && raw != resolved {
+                *value = raw.clone();
+            }
+        }
+    }
+}
+
+/// Returns the name of the first unset variable as error
+fn interpolate(value: &str) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        if let Some(literal) = rest[..start].strip_suffix('$') {
+            result.push_str(literal);
+            result.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        result.push_str(&rest[..start]);
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        };
+
+        let var = &rest[start + 2..end];
+        result.push_str(&env::var(var).map_err(|_| var.to_string())?);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+fn run_command(command: &str) -> Result<String, String> {
+    if let Some(output) = COMMAND_OUTPUTS.lock().unwrap().get(command) {
+        return Ok(output.clone());
+    }
+
+    let output = shell(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("could not run `{}`: {}", command, e))?;
+
+    if !output.status.success() {
+        return Err(format!("`{}` exited with {}", command, output.status));
+    }
+
+    let output = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    COMMAND_OUTPUTS
+        .lock()
+        .unwrap()
+        .insert(command.to_string(), output.clone());
+
+    Ok(output)
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(toml: &str) -> Value {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn variables_are_replaced() {
+        env::set_var("TIMEULAR_TEST_TOKEN", "abc");
+
+        assert_eq!(
+            interpolate("Bearer ${TIMEULAR_TEST_TOKEN}!").unwrap(),
+            "Bearer abc!"
+        );
+        assert_eq!(
+            interpolate("$${TIMEULAR_TEST_TOKEN}").unwrap(),
+            "${TIMEULAR_TEST_TOKEN}"
+        );
+        assert_eq!(interpolate("${unterminated").unwrap(), "${unterminated");
+    }
+
+    #[test]
+    fn unset_variable_is_an_error() {
+        assert_eq!(
+            interpolate("${TIMEULAR_TEST_UNSET}"),
+            Err(String::from("TIMEULAR_TEST_UNSET"))
+        );
+
+        let mut value = table("url = \"${TIMEULAR_TEST_UNSET}\"");
+        let error = resolve("webhook", &mut value, &[]).unwrap_err();
+        assert!(error.contains("TIMEULAR_TEST_UNSET used in webhook.url"));
+    }
+
+    #[test]
+    fn nested_values_are_resolved() {
+        env::set_var("TIMEULAR_TEST_TAG", "billable");
+        let mut value = table("[[sides]]\ntags = [\"${TIMEULAR_TEST_TAG}\"]");
+
+        resolve("watson", &mut value, &[]).unwrap();
+
+        assert_eq!(value, table("[[sides]]\ntags = [\"billable\"]"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn only_secret_fields_are_read_from_commands() {
+        let mut value =
+            table("password_command = \"echo secret\"\ntimew_command = \"echo not-a-secret\"");
+
+        resolve("toggl", &mut value, &["password"]).unwrap();
+
+        assert_eq!(value.get("password"), Some(&Value::from("secret")));
+        assert_eq!(value.get("timew"), None);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn failing_command_is_an_error() {
+        let mut value = table("password_command = \"exit 3\"");
+
+        let error = resolve("toggl", &mut value, &["password"]).unwrap_err();
+
+        assert!(error.starts_with("toggl.password_command: `exit 3` exited with"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn command_runs_once() {
+        let raw = table("password_command = \"date +%s%N\"");
+        let mut first = raw.clone();
+        let mut second = raw.clone();
+
+        resolve("toggl", &mut first, &["password"]).unwrap();
+        resolve("toggl", &mut second, &["password"]).unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn unchanged_values_are_restored() {
+        let raw = table("url = \"${URL}\"\npassword_command = \"pass toggl\"\nname = \"a\"");
+        let resolved = table(
+            "url = \"https://x\"\npassword_command = \"pass toggl\"\npassword = \"secret\"\nname = \"a\"",
+        );
+        let mut value = table("url = \"https://x\"\npassword = \"secret\"\nname = \"b\"");
+
+        restore(&mut value, &raw, &resolved, &["password"]);
+
+        assert_eq!(
+            value,
+            table("url = \"${URL}\"\npassword_command = \"pass toggl\"\nname = \"b\"")
+        );
+    }
+
+    #[test]
+    fn changed_values_are_kept() {
+        let raw = table("url = \"${URL}\"\npassword_command = \"pass toggl\"");
+        let resolved = table("url = \"https://x\"\npassword = \"secret\"");
+        let mut value = table("url = \"https://y\"\npassword = \"new\"");
+
+        restore(&mut value, &raw, &resolved, &["password"]);
+
+        assert_eq!(
+            value,
+            table("url = \"https://y\"\npassword = \"new\"\npassword_command = \"pass toggl\"")
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use std::fs;
+
+use log::{error, warn};
+use simplelog::info;
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+use crate::config::{file, get_config_path};
+
+pub const CONFIG_VERSION: i64 = 1;
+pub const VERSION_KEY: &str = "version";
+
+/// Something a migration changed, a warning when the user has to finish the change
+#[derive(Debug, PartialEq)]
+enum Change {
+    Info(String),
+    Warning(String),
+}
+
+/// A migration upgrades the config by one version and describes every change it made
+type Migration = fn(&mut DocumentMut) -> Vec<Change>;
+
+/// `MIGRATIONS[n]` upgrades a config from version `n` to `n + 1`
+const MIGRATIONS: [Migration; CONFIG_VERSION as usize] = [move_project_id_to_sides];
+
+/// Upgrades the config file to `CONFIG_VERSION`, the old file is kept as `config.toml.v<n>.bak`
+pub fn migrate() {
+    let path = get_config_path();
+    if !path.exists() {
+        return;
+    }
+
+    let _lock = file::lock(path);
+    let mut document = match fs::read_to_string(path).unwrap().parse::<DocumentMut>() {
+        Ok(document) => document,
+        // The config check reports it
+        Err(_) => return,
+    };
+
+    let version = get_version(&document);
+    if version > CONFIG_VERSION {
+        warn!(
+            "Config version {} is newer than the supported version {}, some settings may be ignored",
+            version, CONFIG_VERSION
+        );
+        return;
+    }
+    if version == CONFIG_VERSION {
+        return;
+    }
+
+    if document.is_empty() {
+        upgrade(&mut document, version);
+        file::write(path, &document);
+        return;
+    }
+
+    let backup = file::with_extension(path, &format!("v{}.bak", version));
+    if let Err(e) = fs::copy(path, &backup) {
+        error!("Could not back up the config, not migrating it: {}", e);
+        return;
+    }
+
+    for (to, change) in upgrade(&mut document, version) {
+        match change {
+            Change::Info(change) => info!("Config migrated to version {}: {}", to, change),
+            Change::Warning(change) => warn!("Config migrated to version {}: {}", to, change),
+        }
+    }
+    info!(
+        "Config upgraded from version {} to {}, the old config is saved in {}",
+        version,
+        CONFIG_VERSION,
+        backup.display()
+    );
+
+    file::write(path, &document);
+}
+
+/// Configs from before the version was added have none
+fn get_version(document: &DocumentMut) -> i64 {
+    match document.get(VERSION_KEY) {
+        Some(version) => version.as_integer().unwrap_or(CONFIG_VERSION),
+        None => 0,
+    }
+}
+
+/// Runs the migrations from `version` on, returns the changes with the version they upgraded to
+fn upgrade(document: &mut DocumentMut, version: i64) -> Vec<(i64, Change)> {
+    let mut changes = vec![];
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let to = from as i64 + 1;
+        changes.extend(migration(document).into_iter().map(|change| (to, change)));
+    }
+    document.insert(VERSION_KEY, value(CONFIG_VERSION));
+
+    changes
+}
+
+/// Version 1 lets Toggl, Clockify and Hackaru use a different project per side, the global
+/// `project_id` is set on every labeled side. Without labeled sides it's kept, sides without
+/// their own `project_id` fall back to it
+fn move_project_id_to_sides(document: &mut DocumentMut) -> Vec<Change> {
+    let side_nums: Vec<i64> = document
+        .get("timeular")
+        .and_then(|timeular| timeular.get("sides"))
+        .and_then(Item::as_array_of_tables)
+        .map(|sides| {
+            sides
+                .iter()
+                .filter(|side| side.get("label").and_then(Item::as_str) != Some(""))
+                .filter_map(|side| side.get("side_num").and_then(Item::as_integer))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut changes = vec![];
+    for key in ["toggl", "clockify", "hackaru"] {
+        let section = match document.get_mut(key).and_then(Item::as_table_mut) {
+            Some(section) => section,
+            None => continue,
+        };
+
+        let mut project_id = match section.remove("project_id").map(Item::into_value) {
+            Some(Ok(project_id)) => project_id,
+            _ => continue,
+        };
+        project_id.decor_mut().clear();
+
+        let is_set = match (project_id.as_integer(), project_id.as_str()) {
+            (Some(id), _) => id != 0,
+            (_, Some(id)) => !id.is_empty(),
+            _ => false,
+        };
+        if !is_set {
+            changes.push(Change::Info(format!(
+                "removed the empty {}.project_id",
+                key
+            )));
+            continue;
+        }
+        if side_nums.is_empty() {
+            changes.push(Change::Warning(format!(
+                "kept {}.project_id {} because no side is labeled, it is used for sides without their own project_id in [[{}.sides]]",
+                key, project_id, key
+            )));
+            section.insert("project_id", value(project_id));
+            continue;
+        }
+
+        let mut sides = ArrayOfTables::new();
+        for side_num in &side_nums {
+            let mut side = Table::new();
+            side.insert("side_num", value(*side_num));
+            side.insert("project_id", value(project_id.clone()));
+            sides.push(side);
+        }
+        section.insert("sides", Item::ArrayOfTables(sides));
+
+        changes.push(Change::Info(format!(
+            "moved {}.project_id {} to sides {:?}",
+            key, project_id, side_nums
+        )));
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(toml: &str) -> DocumentMut {
+        toml.parse().unwrap()
+    }
+
+    const SIDES: &str = "[timeular]\nhandler = \"toggl\"\n\n\
+                         [[timeular.sides]]\nside_num = 1\nlabel = \"Code\"\n\n\
+                         [[timeular.sides]]\nside_num = 2\nlabel = \"\"\n\n\
+                         [[timeular.sides]]\nside_num = 3\nlabel = \"Meetings\"\n";
+
+    #[test]
+    fn version_defaults_to_zero() {
+        assert_eq!(get_version(&document("[timeular]\n")), 0);
+        assert_eq!(get_version(&document("version = 1\n")), 1);
+    }
+
+    #[test]
+    fn upgrade_sets_the_version() {
+        let mut document = document("[csv]\npath = \"a.csv\"\n");
+
+        let changes = upgrade(&mut document, 0);
+
+        assert!(changes.is_empty());
+        assert_eq!(
+            document.to_string(),
+            "version = 1\n[csv]\npath = \"a.csv\"\n"
+        );
+    }
+
+    #[test]
+    fn current_version_runs_no_migrations() {
+        let mut document = document(&format!(
+            "version = 1\n{}\n[toggl]\nproject_id = 5\n",
+            SIDES
+        ));
+
+        assert!(upgrade(&mut document, CONFIG_VERSION).is_empty());
+        assert_eq!(document["toggl"]["project_id"].as_integer(), Some(5));
+    }
+
+    #[test]
+    fn project_id_is_moved_to_labeled_sides() {
+        let mut document = document(&format!(
+            "{}\n[toggl]\nproject_id = 5\n\n[clockify]\nproject_id = \"abc\"\n",
+            SIDES
+        ));
+
+        let changes = upgrade(&mut document, 0);
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    1,
+                    Change::Info(String::from("moved toggl.project_id 5 to sides [1, 3]"))
+                ),
+                (
+                    1,
+                    Change::Info(String::from(
+                        "moved clockify.project_id \"abc\" to sides [1, 3]"
+                    ))
+                )
+            ]
+        );
+        let toggl = document["toggl"].as_table().unwrap();
+        assert!(!toggl.contains_key("project_id"));
+        let sides = toggl["sides"].as_array_of_tables().unwrap();
+        assert_eq!(sides.len(), 2);
+        assert_eq!(sides.get(1).unwrap()["side_num"].as_integer(), Some(3));
+        assert_eq!(sides.get(1).unwrap()["project_id"].as_integer(), Some(5));
+        assert_eq!(
+            document["clockify"]["sides"][0]["project_id"].as_str(),
+            Some("abc")
+        );
+    }
+
+    #[test]
+    fn empty_project_id_is_removed() {
+        let mut document = document(&format!("{}\n[hackaru]\nproject_id = 0\n", SIDES));
+
+        let changes = upgrade(&mut document, 0);
+
+        assert_eq!(
+            changes,
+            vec![(
+                1,
+                Change::Info(String::from("removed the empty hackaru.project_id"))
+            )]
+        );
+        assert!(document["hackaru"].get("project_id").is_none());
+        assert!(document["hackaru"].get("sides").is_none());
+    }
+
+    #[test]
+    fn project_id_is_kept_without_labeled_sides() {
+        let mut document = document("[timeular]\nhandler = \"toggl\"\n\n[toggl]\nproject_id = 5\n");
+
+        let changes = upgrade(&mut document, 0);
+
+        assert!(matches!(changes.as_slice(), [(1, Change::Warning(_))]));
+        assert_eq!(document["toggl"]["project_id"].as_integer(), Some(5));
+        assert!(document["toggl"].get("sides").is_none());
+    }
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::sync::OnceLock;
+
+use simplelog::info;
+use toml::Value;
+use toml_edit::value;
+
+use crate::config::diagnostic::suggest;
+use crate::config::{ensure_file_exists, file, get_config_path};
+
+pub const PROFILES_KEY: &str = "profiles";
+pub const ACTIVE_PROFILE_KEY: &str = "active_profile";
+/// Selects the sections outside of `[profiles]`
+pub const DEFAULT_PROFILE: &str = "default";
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Pins the profile for this run, changing `active_profile` has no effect then
+pub fn init_profile(profile: Option<String>) -> Result<(), String> {
+    let profile = match profile {
+        Some(profile) => profile,
+        None => return Ok(()),
+    };
+
+    // An unreadable config has no profiles, the config check reports why
+    let profiles = fs::read_to_string(get_config_path())
+        .ok()
+        .and_then(|contents| toml::from_str::<Value>(&contents).ok())
+        .map(|config| list_profiles(&config))
+        .unwrap_or_default();
+    check_profile(&profile, &profiles)?;
+
+    PROFILE
+        .set(profile)
+        .map_err(|profile| format!("Profile is already set, can't use {}", profile))
+}
+
+/// The profile from `--profile` or `active_profile`, `None` for the default profile
+pub fn active_profile(config: &Value) -> Option<String> {
+    let profile = match PROFILE.get() {
+        Some(profile) => profile.clone(),
+        None => config.get(ACTIVE_PROFILE_KEY)?.as_str()?.to_string(),
+    };
+
+    Some(profile).filter(|profile| profile != DEFAULT_PROFILE)
+}
+
+/// The section `key` is read from the active profile, or from outside of `[profiles]` if the
+/// profile doesn't have it, so profiles only need the sections that differ
+pub fn read_path(config: &Value, key: &str) -> Vec<String> {
+    let path = write_path(config, key);
+    if get(config, &path).is_some() {
+        return path;
+    }
+
+    vec![key.to_string()]
+}
+
+/// The section `key` is always written to the active profile
+pub fn write_path(config: &Value, key: &str) -> Vec<String> {
+    match active_profile(config) {
+        Some(profile) => vec![PROFILES_KEY.to_string(), profile, key.to_string()],
+        None => vec![key.to_string()],
+    }
+}
+
+pub fn get<'a>(config: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(config, |value, key| value.get(key))
+}
+
+pub fn list_profiles(config: &Value) -> Vec<String> {
+    config
+        .get(PROFILES_KEY)
+        .and_then(Value::as_table)
+        .map(|profiles| profiles.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Prints every profile, the active one is marked with `*`
+pub fn print_profiles() {
+    let config: Value = match fs::read_to_string(get_config_path()) {
+        Ok(contents) => toml::from_str(&contents).unwrap_or(Value::Table(Default::default())),
+        Err(_) => Value::Table(Default::default()),
+    };
+    let active = active_profile(&config).unwrap_or(DEFAULT_PROFILE.to_string());
+
+    let mut message = String::from("Profiles:");
+    for profile in [DEFAULT_PROFILE.to_string()]
+        .into_iter()
+        .chain(list_profiles(&config))
+    {
+        let marker = if profile == active { "*" } else { " " };
+        message.push_str(format!("\n{} {}", marker, profile).as_str());
+    }
+    info!("{}", message);
+}
+
+/// Sets `active_profile`, running readers switch to it on the next config reload
+pub fn use_profile(name: &str) -> Result<(), String> {
+    ensure_file_exists();
+
+    let path = get_config_path();
+    let _lock = file::lock(path);
+    let mut document = file::read(path);
+    let config: Value = toml::from_str(&document.to_string()).map_err(|e| e.to_string())?;
+
+    check_profile(name, &list_profiles(&config))?;
+
+    if name == DEFAULT_PROFILE {
+        document.remove(ACTIVE_PROFILE_KEY);
+    } else {
+        document.insert(ACTIVE_PROFILE_KEY, value(name));
+    }
+    file::write(path, &document);
+
+    Ok(())
+}
+
+fn check_profile(name: &str, profiles: &[String]) -> Result<(), String> {
+    if name == DEFAULT_PROFILE || profiles.iter().any(|profile| profile == name) {
+        return Ok(());
+    }
+
+    let mut message = format!("Unknown profile \"{}\"", name);
+    let candidates = profiles.iter().map(String::as_str).chain([DEFAULT_PROFILE]);
+    if let Some(suggestion) = suggest(name, candidates) {
+        message.push_str(format!(", did you mean \"{}\"?", suggestion).as_str());
+    }
+
+    Err(message)
+}
@@ -0,0 +1,28 @@
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::error;
+
+static NO_PROMPT: AtomicBool = AtomicBool::new(false);
+
+/// With `--no-prompt` nothing is read from stdin, values have to come from the config file
+pub fn init_no_prompt(no_prompt: bool) {
+    NO_PROMPT.store(no_prompt, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    !NO_PROMPT.load(Ordering::Relaxed)
+}
+
+/// Called before asking for a value the handler can't work without, exits if prompting is disabled
+pub fn require(field: &str) {
+    if is_enabled() {
+        return;
+    }
+
+    error!(
+        "{} is not set, add it to the config file or run with --setup (prompting is disabled by --no-prompt)",
+        field
+    );
+    process::exit(1);
+}
@@ -0,0 +1,45 @@
+use std::sync::Once;
+
+use keyring::Entry;
+use log::debug;
+use simplelog::info;
+
+const SERVICE: &str = "timeular-reader";
+const REFERENCE_PREFIX: &str = "keyring:";
+
+static FALLBACK_NOTICE: Once = Once::new();
+
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(REFERENCE_PREFIX)
+}
+
+/// Saves the secret in the system keyring under `account`, returns the reference to keep
+/// in the config instead of the secret, or `None` when no keyring is available
+pub fn store(account: &str, secret: &str) -> Option<String> {
+    let result = Entry::new(SERVICE, account).and_then(|entry| entry.set_password(secret));
+
+    match result {
+        Ok(_) => {
+            debug!("Secret {} saved in the keyring", account);
+            Some(format!("{}{}", REFERENCE_PREFIX, account))
+        }
+        Err(e) => {
+            debug!("Could not save secret {} in the keyring: {}", account, e);
+            FALLBACK_NOTICE.call_once(|| {
+                info!("System keyring is not available, secrets are stored in the config file")
+            });
+            None
+        }
+    }
+}
+
+/// Reads the secret the reference points to
+pub fn resolve(reference: &str) -> Result<String, String> {
+    let account = reference
+        .strip_prefix(REFERENCE_PREFIX)
+        .ok_or_else(|| format!("{} is not a keyring reference", reference))?;
+
+    Entry::new(SERVICE, account)
+        .and_then(|entry| entry.get_password())
+        .map_err(|e| format!("could not read secret {} from the keyring: {}", account, e))
+}
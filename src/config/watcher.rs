@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use log::debug;
+use tokio::sync::watch;
+
+use crate::config::{file, get_config_path};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Notifies the receiver every time the config file changes on disk, changes written by the
+/// reader itself are skipped
+pub fn watch_config() -> watch::Receiver<()> {
+    let (sender, receiver) = watch::channel(());
+    let path = get_config_path().to_path_buf();
+
+    tokio::spawn(async move {
+        let mut last_modified = modified(&path);
+
+        while !sender.is_closed() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let modified = modified(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            if fs::read_to_string(&path).is_ok_and(|contents| file::is_own_write(&contents)) {
+                debug!("config file written by the reader, not reloading");
+                continue;
+            }
+
+            debug!("config file changed");
+            let _ = sender.send(());
+        }
+    });
+
+    receiver
+}
+
+fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+    fs::metadata(path)
+        .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+        .ok()
+}
@@ -0,0 +1,536 @@
+use std::process;
+
+use log::error;
+use simplelog::info;
+use toml::{value::Table, Value};
+
+use crate::config::{prompt, try_get_config, update_config, Config};
+use crate::tracker::config::{get_timeular_config, Side};
+
+/// Checks the input before it's saved, the error is shown and the input asked for again
+pub type Validator = fn(&str) -> Result<(), String>;
+/// Decides from the config, or the side for a `per_side` field, if the field is asked for
+pub type Condition = fn(&Table) -> bool;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Text,
+    Number,
+    Flag,
+    Choice,
+}
+
+/// A value of a handler config the setup asks for, `name` is its key in the config
+#[derive(Debug, Clone)]
+pub struct Field {
+    name: &'static str,
+    description: &'static str,
+    kind: Kind,
+    secret: bool,
+    required: bool,
+    per_side: bool,
+    default: Option<&'static str>,
+    validator: Option<Validator>,
+    condition: Option<Condition>,
+    choices: Vec<(i64, String)>,
+}
+
+impl Field {
+    pub fn text(name: &'static str, description: &'static str) -> Self {
+        Field::new(name, description, Kind::Text)
+    }
+
+    pub fn number(name: &'static str, description: &'static str) -> Self {
+        Field::new(name, description, Kind::Number)
+    }
+
+    /// A yes or no question, saved as a boolean
+    pub fn flag(name: &'static str, description: &'static str) -> Self {
+        Field::new(name, description, Kind::Flag)
+    }
+
+    /// One of the `choices`, picked by its position in the list and saved as its id
+    pub fn choice(
+        name: &'static str,
+        description: &'static str,
+        choices: Vec<(i64, String)>,
+    ) -> Self {
+        Field {
+            choices,
+            ..Field::new(name, description, Kind::Choice)
+        }
+    }
+
+    fn new(name: &'static str, description: &'static str, kind: Kind) -> Self {
+        Field {
+            name,
+            description,
+            kind,
+            secret: false,
+            required: false,
+            per_side: false,
+            default: None,
+            validator: None,
+            condition: None,
+            choices: vec![],
+        }
+    }
+
+    /// The input is not shown, list the field in `Config::secret_fields` to keep it in the keyring
+    pub fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    /// Asked for on every start until it's set, not only during `--setup`. For a `per_side`
+    /// field the side is skipped when it's left blank
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Asked for every labeled side and saved in the `sides` of the config
+    pub fn per_side(mut self) -> Self {
+        self.per_side = true;
+        self
+    }
+
+    /// Used when the field is not set and the input is left blank
+    pub fn default(mut self, default: &'static str) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn validate(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Only asked for when the condition holds, e.g. a password only for basic auth
+    pub fn only_if(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    fn applies(&self, table: &Table) -> bool {
+        self.condition.is_none_or(|condition| condition(table))
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        if let Some(validator) = self.validator {
+            validator(input)?;
+        }
+
+        match self.kind {
+            Kind::Text => Ok(Value::String(input.to_string())),
+            Kind::Number => match input.parse::<i64>() {
+                Ok(number) if number > 0 => Ok(Value::Integer(number)),
+                _ => Err(String::from("Please provide a positive number")),
+            },
+            Kind::Flag => match input.to_lowercase().as_str() {
+                "y" | "yes" => Ok(Value::Boolean(true)),
+                "n" | "no" => Ok(Value::Boolean(false)),
+                _ => Err(String::from("Please answer y or n")),
+            },
+            Kind::Choice => match input.parse::<usize>() {
+                Ok(i) if i >= 1 && i <= self.choices.len() => {
+                    Ok(Value::Integer(self.choices[i - 1].0))
+                }
+                _ => Err(format!(
+                    "Please choose a number between 1 and {}",
+                    self.choices.len()
+                )),
+            },
+        }
+    }
+
+    /// The description of a flag is the question itself
+    fn question(&self) -> String {
+        match self.kind {
+            Kind::Flag => format!("{}?", self.description),
+            Kind::Choice => format!("Choose {}", self.description),
+            _ => format!("Provide your {}", self.description),
+        }
+    }
+
+    fn display(&self, value: &Value) -> String {
+        match (self.kind, value) {
+            (Kind::Flag, Value::Boolean(true)) => String::from("y"),
+            (Kind::Flag, Value::Boolean(false)) => String::from("n"),
+            (Kind::Choice, Value::Integer(id)) => self
+                .choices
+                .iter()
+                .find(|(choice, _)| choice == id)
+                .map_or(id.to_string(), |(_, label)| label.clone()),
+            (_, Value::String(value)) => value.clone(),
+            (_, value) => value.to_string(),
+        }
+    }
+}
+
+pub fn url(input: &str) -> Result<(), String> {
+    if input.starts_with("http://") || input.starts_with("https://") {
+        return Ok(());
+    }
+
+    Err(String::from(
+        "Please provide a url starting with http:// or https://",
+    ))
+}
+
+pub fn email(input: &str) -> Result<(), String> {
+    match input.split_once('@') {
+        Some((user, domain)) if !user.is_empty() && !domain.is_empty() => Ok(()),
+        _ => Err(String::from("Please provide an email address")),
+    }
+}
+
+/// Asks for the `fields` of the config under `key`. During setup every field is asked for,
+/// otherwise only the required ones that are not set. The `per_side` fields are asked for
+/// side by side after the others, only during setup. The config is saved once at the end
+pub fn run<'de, T: Config<'de>>(key: &str, config: &mut T, fields: &[Field], setup: bool) {
+    let mut value = Value::try_from(&*config).unwrap();
+    let table = value
+        .as_table_mut()
+        .expect("Handler config should be a table");
+    let mut changed = false;
+
+    for field in fields.iter().filter(|field| !field.per_side) {
+        if !field.applies(table) {
+            continue;
+        }
+
+        let current = table.get(field.name).filter(|v| is_set(v)).cloned();
+        if !setup && (current.is_some() || !field.required) {
+            continue;
+        }
+
+        if let Some(new) = ask(key, field, field.question(), current.as_ref()) {
+            table.insert(field.name.to_string(), new);
+            changed = true;
+        }
+    }
+
+    let side_fields: Vec<&Field> = fields.iter().filter(|field| field.per_side).collect();
+    if setup && !side_fields.is_empty() {
+        changed |= ask_sides(key, table, &side_fields);
+    }
+
+    if changed {
+        *config = value
+            .try_into()
+            .expect("Setup fields should match the config");
+        update_config(key, config);
+    }
+}
+
+/// Fails instead of asking when a required field of the config under `key` is not set, or the
+/// config is invalid. Used where nobody can answer, e.g. when the config is reloaded
+pub fn check<'de, T: Config<'de>>(key: &str, fields: &[Field]) -> Result<(), String> {
+    let config = try_get_config::<T>(key)?.unwrap_or_default();
+    let value = Value::try_from(&config).unwrap();
+    let table = value.as_table().expect("Handler config should be a table");
+
+    let missing = missing(key, table, fields);
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "{} is not set, add it to the config file or run with --setup",
+        missing.join(", ")
+    ))
+}
+
+/// The required fields `run` would ask for outside of setup
+fn missing(key: &str, table: &Table, fields: &[Field]) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|field| field.required && !field.per_side && field.applies(table))
+        .filter(|field| !table.get(field.name).is_some_and(is_set))
+        .map(|field| format!("{}.{}", key, field.name))
+        .collect()
+}
+
+fn ask_sides(key: &str, table: &mut Table, fields: &[&Field]) -> bool {
+    let mut sides: Vec<Table> = table
+        .get("sides")
+        .and_then(Value::as_array)
+        .map(|sides| sides.iter().filter_map(|s| s.as_table().cloned()).collect())
+        .unwrap_or_default();
+    let mut changed = false;
+
+    for side in get_timeular_config().sides {
+        if side.label.is_empty() {
+            continue;
+        }
+
+        let side_num = Value::Integer(side.side_num.into());
+        let index = sides
+            .iter()
+            .position(|s| s.get("side_num") == Some(&side_num));
+        let mut entry = match index {
+            Some(i) => sides[i].clone(),
+            None => Table::from_iter([(String::from("side_num"), side_num)]),
+        };
+
+        if !ask_side(key, &mut entry, fields, &side) {
+            continue;
+        }
+
+        match index {
+            Some(i) => sides[i] = entry,
+            None => sides.push(entry),
+        }
+        changed = true;
+    }
+
+    if changed {
+        sides.sort_by_key(|s| s.get("side_num").and_then(Value::as_integer));
+        table.insert(
+            String::from("sides"),
+            Value::Array(sides.into_iter().map(Value::Table).collect()),
+        );
+    }
+
+    changed
+}
+
+/// Returns whether the side changed, a required field left blank skips the whole side
+fn ask_side(key: &str, entry: &mut Table, fields: &[&Field], side: &Side) -> bool {
+    let mut changed = false;
+
+    for field in fields {
+        if !field.applies(entry) {
+            continue;
+        }
+
+        let current = entry.get(field.name).filter(|v| is_set(v)).cloned();
+        let message = format!("{} for side {}", field.question(), side);
+        match ask(key, field, message, current.as_ref()) {
+            Some(new) => {
+                entry.insert(field.name.to_string(), new);
+                changed = true;
+            }
+            None if field.required && current.is_none() => {
+                info!("Side {} skipped", side);
+                return false;
+            }
+            None => {}
+        }
+    }
+
+    changed
+}
+
+/// Asks for a `choice` field, outside of a config or for one `side`. Returns the chosen id, or
+/// `current` when it's left blank and still one of the choices, `None` if nothing is chosen
+pub fn choose(key: &str, field: &Field, side: Option<&Side>, current: Option<i64>) -> Option<i64> {
+    if field.choices.is_empty() {
+        info!("Nothing to choose {} from", field.description);
+        return None;
+    }
+
+    let current = current
+        .filter(|id| field.choices.iter().any(|(choice, _)| choice == id))
+        .map(Value::Integer);
+    let message = match side {
+        Some(side) => format!("{} for side {}", field.question(), side),
+        None => field.question(),
+    };
+
+    ask(key, field, message, current.as_ref())
+        .or(current)
+        .and_then(|value| value.as_integer())
+}
+
+/// Returns the new value, `None` keeps the current one
+fn ask(key: &str, field: &Field, mut message: String, current: Option<&Value>) -> Option<Value> {
+    let name = format!("{}.{}", key, field.name);
+    let must_answer =
+        field.required && !field.per_side && current.is_none() && field.default.is_none();
+    prompt::require(&name);
+
+    match field.kind {
+        Kind::Flag => message.push_str(" [y/n]"),
+        Kind::Choice => {
+            message.push_str(format!(" [1-{}]", field.choices.len()).as_str());
+            for (i, (_, label)) in field.choices.iter().enumerate() {
+                message.push_str(format!("\n{}: {}", i + 1, label).as_str());
+            }
+        }
+        _ => {}
+    }
+    match (current, field.default) {
+        (Some(_), _) if field.secret => message.push_str("\nleave blank to use current value"),
+        (Some(current), _) => message.push_str(
+            format!(
+                "\ncurrent value {}, leave blank to skip",
+                field.display(current)
+            )
+            .as_str(),
+        ),
+        (None, Some(default)) => {
+            message.push_str(format!("\nleave blank to use {}", default).as_str())
+        }
+        (None, None) if !must_answer => message.push_str("\nleave blank to skip"),
+        (None, None) => {}
+    }
+    info!("{message}");
+
+    loop {
+        let input = match read(field) {
+            Some(input) => input,
+            None if must_answer => {
+                error!("No input, {} is not set", name);
+                process::exit(1);
+            }
+            None => return None,
+        };
+
+        if input.is_empty() {
+            match (current, field.default) {
+                (None, Some(default)) => return field.parse(default).ok(),
+                _ if must_answer => info!("{} is required", field.description),
+                _ => return None,
+            }
+            continue;
+        }
+
+        match field.parse(&input) {
+            Ok(value) => return Some(value),
+            Err(e) => info!("{}", e),
+        }
+    }
+}
+
+/// Reads a line of input, `None` if stdin is closed
+fn read(field: &Field) -> Option<String> {
+    if field.secret {
+        return rpassword::read_password()
+            .ok()
+            .map(|input| input.trim().to_string());
+    }
+
+    let mut input = String::new();
+    match std::io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input.trim().to_string()),
+    }
+}
+
+fn is_set(value: &Value) -> bool {
+    match value {
+        Value::String(value) => !value.is_empty(),
+        Value::Integer(value) => *value != 0,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_must_be_positive() {
+        let field = Field::number("project_id", "project id");
+
+        assert_eq!(field.parse("42"), Ok(Value::Integer(42)));
+        assert!(field.parse("0").is_err());
+        assert!(field.parse("abc").is_err());
+    }
+
+    #[test]
+    fn flag_is_parsed_as_boolean() {
+        let field = Field::flag("live", "Track live");
+
+        assert_eq!(field.parse("Y"), Ok(Value::Boolean(true)));
+        assert_eq!(field.parse("no"), Ok(Value::Boolean(false)));
+        assert!(field.parse("maybe").is_err());
+        assert_eq!(field.display(&Value::Boolean(true)), "y");
+        assert_eq!(field.question(), "Track live?");
+    }
+
+    #[test]
+    fn choice_is_picked_by_position() {
+        let field = Field::choice(
+            "project_id",
+            "project",
+            vec![(7, String::from("Website")), (9, String::from("App"))],
+        );
+
+        assert_eq!(field.parse("2"), Ok(Value::Integer(9)));
+        assert!(field.parse("0").is_err());
+        assert!(field.parse("3").is_err());
+        assert!(field.parse("9").is_err());
+        assert_eq!(field.display(&Value::Integer(7)), "Website");
+        assert_eq!(field.question(), "Choose project");
+    }
+
+    #[test]
+    fn validator_runs_before_parsing() {
+        let field = Field::text("base_url", "url").validate(url);
+
+        assert!(field.parse("example.com").is_err());
+        assert_eq!(
+            field.parse("https://example.com"),
+            Ok(Value::String(String::from("https://example.com")))
+        );
+    }
+
+    #[test]
+    fn condition_decides_if_field_applies() {
+        let field = Field::text("password", "password")
+            .only_if(|config| config.get("auth").and_then(Value::as_str) == Some("basic"));
+        let mut config = Table::new();
+
+        assert!(!field.applies(&config));
+        config.insert(String::from("auth"), Value::String(String::from("basic")));
+        assert!(field.applies(&config));
+        assert!(Field::text("url", "url").applies(&Table::new()));
+    }
+
+    #[test]
+    fn unset_required_fields_are_missing() {
+        let fields = [
+            Field::text("url", "url").required(),
+            Field::text("token", "token").secret().required(),
+            Field::text("password", "password")
+                .required()
+                .only_if(|config| config.contains_key("username")),
+            Field::number("project_id", "project id")
+                .per_side()
+                .required(),
+            Field::text("label", "label"),
+        ];
+        let mut config = Table::new();
+        config.insert(
+            String::from("url"),
+            Value::String(String::from("https://a")),
+        );
+        config.insert(String::from("token"), Value::String(String::new()));
+
+        assert_eq!(missing("webhook", &config, &fields), vec!["webhook.token"]);
+
+        config.insert(String::from("username"), Value::String(String::from("me")));
+        assert_eq!(
+            missing("webhook", &config, &fields),
+            vec!["webhook.token", "webhook.password"]
+        );
+    }
+
+    #[test]
+    fn empty_values_are_not_set() {
+        assert!(!is_set(&Value::String(String::new())));
+        assert!(!is_set(&Value::Integer(0)));
+        assert!(is_set(&Value::Boolean(false)));
+        assert!(is_set(&Value::String(String::from("a"))));
+    }
+
+    #[test]
+    fn email_needs_user_and_domain() {
+        assert!(email("me@example.com").is_ok());
+        assert!(email("@example.com").is_err());
+        assert!(email("me").is_err());
+    }
+}
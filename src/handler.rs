@@ -1,12 +1,27 @@
+use crate::config::diagnostic::{check_section, Diagnostic};
 use crate::tracker::config::{Handler, TimeularConfig};
 use serde_derive::{Deserialize, Serialize};
 use strum::EnumIter;
+use toml_edit::Item;
 
+pub mod activitywatch;
+pub mod caldav;
 pub mod clockify;
+pub mod csv;
 pub mod example;
 pub mod hackaru;
+pub mod harvest;
+pub mod ical;
+pub mod jira;
+pub mod kimai;
+pub mod org;
+pub mod redmine;
+pub mod timeclock;
+pub mod timewarrior;
 pub mod toggl;
 pub mod traggo;
+pub mod watson;
+pub mod webhook;
 
 #[derive(Serialize, Deserialize, EnumIter, Debug)]
 pub enum Handlers {
@@ -15,6 +30,19 @@ pub enum Handlers {
     Traggo = 3,
     Hackaru = 4,
     Example = 5,
+    Csv = 6,
+    Webhook = 7,
+    Timewarrior = 8,
+    Watson = 9,
+    Org = 10,
+    Timeclock = 11,
+    Ical = 12,
+    Caldav = 13,
+    Kimai = 14,
+    Harvest = 15,
+    Jira = 16,
+    Redmine = 17,
+    ActivityWatch = 18,
 }
 impl TryFrom<u8> for Handlers {
     type Error = ();
@@ -26,6 +54,19 @@ impl TryFrom<u8> for Handlers {
             x if x == Handlers::Traggo as u8 => Ok(Handlers::Traggo),
             x if x == Handlers::Hackaru as u8 => Ok(Handlers::Hackaru),
             x if x == Handlers::Example as u8 => Ok(Handlers::Example),
+            x if x == Handlers::Csv as u8 => Ok(Handlers::Csv),
+            x if x == Handlers::Webhook as u8 => Ok(Handlers::Webhook),
+            x if x == Handlers::Timewarrior as u8 => Ok(Handlers::Timewarrior),
+            x if x == Handlers::Watson as u8 => Ok(Handlers::Watson),
+            x if x == Handlers::Org as u8 => Ok(Handlers::Org),
+            x if x == Handlers::Timeclock as u8 => Ok(Handlers::Timeclock),
+            x if x == Handlers::Ical as u8 => Ok(Handlers::Ical),
+            x if x == Handlers::Caldav as u8 => Ok(Handlers::Caldav),
+            x if x == Handlers::Kimai as u8 => Ok(Handlers::Kimai),
+            x if x == Handlers::Harvest as u8 => Ok(Handlers::Harvest),
+            x if x == Handlers::Jira as u8 => Ok(Handlers::Jira),
+            x if x == Handlers::Redmine as u8 => Ok(Handlers::Redmine),
+            x if x == Handlers::ActivityWatch as u8 => Ok(Handlers::ActivityWatch),
             _ => Err(()),
         }
     }
@@ -41,6 +82,19 @@ impl TryFrom<&String> for Handlers {
             "traggo" => Ok(Handlers::Traggo),
             "hackaru" => Ok(Handlers::Hackaru),
             "example" => Ok(Handlers::Example),
+            "csv" => Ok(Handlers::Csv),
+            "webhook" => Ok(Handlers::Webhook),
+            "timewarrior" => Ok(Handlers::Timewarrior),
+            "watson" => Ok(Handlers::Watson),
+            "org" => Ok(Handlers::Org),
+            "timeclock" => Ok(Handlers::Timeclock),
+            "ical" => Ok(Handlers::Ical),
+            "caldav" => Ok(Handlers::Caldav),
+            "kimai" => Ok(Handlers::Kimai),
+            "harvest" => Ok(Handlers::Harvest),
+            "jira" => Ok(Handlers::Jira),
+            "redmine" => Ok(Handlers::Redmine),
+            "activitywatch" => Ok(Handlers::ActivityWatch),
             _ => Err(()),
         }
     }
@@ -53,6 +107,89 @@ pub async fn get_handler(setup: bool, config: &TimeularConfig) -> Box<dyn Handle
         "clockify" => Box::new(clockify::create_handler(setup).await),
         "traggo" => Box::new(traggo::create_handler(setup).await),
         "example" => Box::new(example::create_handler(setup).await),
+        "csv" => Box::new(csv::create_handler(setup).await),
+        "webhook" => Box::new(webhook::create_handler(setup).await),
+        "timewarrior" => Box::new(timewarrior::create_handler(setup).await),
+        "watson" => Box::new(watson::create_handler(setup).await),
+        "org" => Box::new(org::create_handler(setup).await),
+        "timeclock" => Box::new(timeclock::create_handler(setup).await),
+        "ical" => Box::new(ical::create_handler(setup).await),
+        "caldav" => Box::new(caldav::create_handler(setup).await),
+        "kimai" => Box::new(kimai::create_handler(setup).await),
+        "harvest" => Box::new(harvest::create_handler(setup).await),
+        "jira" => Box::new(jira::create_handler(setup).await),
+        "redmine" => Box::new(redmine::create_handler(setup).await),
+        "activitywatch" => Box::new(activitywatch::create_handler(setup).await),
         _ => Box::new(example::create_handler(setup).await),
     }
 }
+
+/// Same as `get_handler` without setup, but fails instead of asking for missing settings or
+/// exiting, so a running reader can keep its current handler
+pub async fn try_get_handler(config: &TimeularConfig) -> Result<Box<dyn Handler>, String> {
+    match config.handler.as_str() {
+        "toggl" => toggl::config::check_required()?,
+        "hackaru" => hackaru::config::check_required()?,
+        "clockify" => clockify::config::check_required()?,
+        "example" => example::config::check_required()?,
+        "csv" => csv::config::check_required()?,
+        "webhook" => webhook::config::check_required()?,
+        "timewarrior" => timewarrior::config::check_required()?,
+        "watson" => watson::config::check_required()?,
+        "org" => org::config::check_required()?,
+        "timeclock" => timeclock::config::check_required()?,
+        "ical" => ical::config::check_required()?,
+        "caldav" => caldav::config::check_required()?,
+        "kimai" => kimai::config::check_required()?,
+        "harvest" => harvest::config::check_required()?,
+        "jira" => jira::config::check_required()?,
+        "redmine" => redmine::config::check_required()?,
+        "activitywatch" => activitywatch::config::check_required()?,
+        "traggo" => {}
+        _ => example::config::check_required()?,
+    }
+
+    Ok(get_handler(false, config).await)
+}
+
+/// Checks the config section of the handler at `path`, without creating the handler
+pub fn check_config(name: &str, path: &[&str], item: &Item, contents: &str) -> Option<Diagnostic> {
+    match name {
+        "toggl" => check_section::<toggl::config::TogglConfig>(contents, path),
+        "hackaru" => check_section::<hackaru::config::HackaruConfig>(contents, path),
+        "clockify" => check_section::<clockify::config::ClockifyConfig>(contents, path),
+        "example" => check_section::<example::config::ExampleConfig>(contents, path),
+        "csv" => check_section::<csv::config::CsvConfig>(contents, path)
+            .or_else(|| csv::config::check_formats(path, item)),
+        "webhook" => check_section::<webhook::config::WebhookConfig>(contents, path),
+        "timewarrior" => check_section::<timewarrior::config::TimewarriorConfig>(contents, path),
+        "watson" => check_section::<watson::config::WatsonConfig>(contents, path),
+        "org" => check_section::<org::config::OrgConfig>(contents, path),
+        "timeclock" => check_section::<timeclock::config::TimeclockConfig>(contents, path),
+        "ical" => check_section::<ical::config::IcalConfig>(contents, path),
+        "caldav" => check_section::<caldav::config::CaldavConfig>(contents, path),
+        "kimai" => check_section::<kimai::config::KimaiConfig>(contents, path),
+        "harvest" => check_section::<harvest::config::HarvestConfig>(contents, path),
+        "jira" => check_section::<jira::config::JiraConfig>(contents, path)
+            .or_else(|| jira::config::check_worklog_uri(path, item)),
+        "redmine" => check_section::<redmine::config::RedmineConfig>(contents, path),
+        "activitywatch" => {
+            check_section::<activitywatch::config::ActivityWatchConfig>(contents, path)
+                .or_else(|| activitywatch::config::check_heartbeat_interval(path, item))
+        }
+        _ => None,
+    }
+}
+
+pub fn migrate_secrets() {
+    toggl::config::migrate_secrets();
+    hackaru::config::migrate_secrets();
+    clockify::config::migrate_secrets();
+    example::config::migrate_secrets();
+    webhook::config::migrate_secrets();
+    caldav::config::migrate_secrets();
+    kimai::config::migrate_secrets();
+    harvest::config::migrate_secrets();
+    jira::config::migrate_secrets();
+    redmine::config::migrate_secrets();
+}
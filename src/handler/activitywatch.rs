@@ -0,0 +1,187 @@
+pub mod config;
+pub mod http_data;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use http_data::*;
+use log::{debug, error};
+use reqwest::{Client, StatusCode};
+use simplelog::info;
+use tokio::task::JoinHandle;
+
+use crate::handler::activitywatch::config::{create_config, setup_config, ActivityWatchConfig};
+use crate::tracker::config::{Handler, Side};
+
+const CLIENT_NAME: &str = "timeular-reader";
+const BUCKET_TYPE: &str = "timeular.side";
+
+#[derive(Debug, Default)]
+pub struct ActivityWatch {
+    client: Client,
+    config: ActivityWatchConfig,
+    bucket_url: String,
+    heartbeat: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ActivityWatch {
+    fn heartbeat_url(&self) -> String {
+        format!(
+            "{}/heartbeat?pulsetime={}",
+            self.bucket_url,
+            self.config.heartbeat_interval + 10
+        )
+    }
+
+    fn stop_heartbeat(&self) {
+        if let Some(heartbeat) = self.heartbeat.lock().unwrap().take() {
+            heartbeat.abort();
+        }
+    }
+}
+
+impl Drop for ActivityWatch {
+    fn drop(&mut self) {
+        self.stop_heartbeat();
+    }
+}
+
+#[async_trait]
+impl Handler for ActivityWatch {
+    async fn start(&self, side: &Side, _start: &DateTime<Local>) {
+        if !self.config.heartbeats {
+            return;
+        }
+
+        self.stop_heartbeat();
+
+        let client = self.client.clone();
+        let url = self.heartbeat_url();
+        let interval = Duration::from_secs(self.config.heartbeat_interval);
+        let data = EventData {
+            label: side.label.clone(),
+            side_num: side.side_num,
+        };
+
+        let heartbeat = tokio::spawn(async move {
+            loop {
+                send_heartbeat(&client, &url, &data, &Utc::now()).await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        *self.heartbeat.lock().unwrap() = Some(heartbeat);
+    }
+
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let data = EventData {
+            label: side.label.clone(),
+            side_num: side.side_num,
+        };
+
+        if self.config.heartbeats {
+            self.stop_heartbeat();
+            // The last heartbeat extends the event until the side was flipped
+            send_heartbeat(
+                &self.client,
+                &self.heartbeat_url(),
+                &data,
+                &duration.1.with_timezone(&Utc),
+            )
+            .await;
+            return;
+        }
+
+        let event = Event {
+            timestamp: duration
+                .0
+                .with_timezone(&Utc)
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            duration: (duration.1 - duration.0).num_milliseconds() as f64 / 1000.0,
+            data,
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/events", self.bucket_url))
+            .json(&vec![event])
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => debug!("Event sent"),
+            Ok(response) => error!(
+                "ActivityWatch responded with {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ),
+            Err(e) => error!("ActivityWatch error {}", e),
+        }
+    }
+}
+
+async fn send_heartbeat(client: &Client, url: &str, data: &EventData, timestamp: &DateTime<Utc>) {
+    let event = Event {
+        timestamp: timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        duration: 0.0,
+        data: data.clone(),
+    };
+
+    match client.post(url).json(&event).send().await {
+        Ok(response) if response.status().is_success() => debug!("Heartbeat sent"),
+        Ok(response) => error!("ActivityWatch responded with {}", response.status()),
+        Err(e) => error!("ActivityWatch error {}", e),
+    }
+}
+
+async fn ensure_bucket_exists(client: &Client, bucket_url: &str, hostname: &str) {
+    match client.get(bucket_url).send().await {
+        Ok(response) if response.status() == StatusCode::NOT_FOUND => {}
+        Ok(_) => return,
+        Err(e) => {
+            error!("Could not connect to ActivityWatch: {}", e);
+            return;
+        }
+    }
+
+    let bucket = BucketRequest {
+        client: String::from(CLIENT_NAME),
+        bucket_type: String::from(BUCKET_TYPE),
+        hostname: hostname.to_string(),
+    };
+
+    match client.post(bucket_url).json(&bucket).send().await {
+        Ok(response) if response.status().is_success() => info!("ActivityWatch bucket created"),
+        Ok(response) => error!(
+            "Could not create ActivityWatch bucket, responded with {}",
+            response.status()
+        ),
+        Err(e) => error!("ActivityWatch error {}", e),
+    }
+}
+
+pub async fn create_handler(setup: bool) -> ActivityWatch {
+    let mut config = create_config();
+    let client = Client::builder().build().unwrap();
+    setup_config(&mut config, setup);
+
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| String::from("unknown"));
+    let bucket_url = format!(
+        "{}/api/0/buckets/{}_{}",
+        config.base_url.trim_end_matches('/'),
+        CLIENT_NAME,
+        hostname
+    );
+    ensure_bucket_exists(&client, &bucket_url, &hostname).await;
+
+    ActivityWatch {
+        client,
+        config,
+        bucket_url,
+        heartbeat: Mutex::new(None),
+    }
+}
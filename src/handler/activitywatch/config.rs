@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use toml::Value;
+use toml_edit::Item;
+
+use crate::config::diagnostic::Diagnostic;
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "activitywatch";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ActivityWatchConfig {
+    pub base_url: String,
+    pub heartbeats: bool,
+    pub heartbeat_interval: u64,
+}
+
+impl Default for ActivityWatchConfig {
+    fn default() -> Self {
+        ActivityWatchConfig {
+            base_url: String::from("http://localhost:5600"),
+            heartbeats: false,
+            heartbeat_interval: 30,
+        }
+    }
+}
+impl<'de> Config<'de> for ActivityWatchConfig {}
+
+/// Reports a `heartbeat_interval` of 0, which would send heartbeats without pause, `item` is the
+/// `[activitywatch]` section at `path`
+pub fn check_heartbeat_interval(path: &[&str], item: &Item) -> Option<Diagnostic> {
+    let heartbeat_interval = item.get("heartbeat_interval")?;
+    if heartbeat_interval.as_integer()? != 0 {
+        return None;
+    }
+
+    Some(Diagnostic::error(
+        format!(
+            "[{}] heartbeat_interval has to be at least 1 second",
+            path.join(".")
+        ),
+        heartbeat_interval.span(),
+    ))
+}
+
+pub fn create_config() -> ActivityWatchConfig {
+    crate::config::get_config::<ActivityWatchConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("base_url", "aw-server url").validate(wizard::url),
+        Field::flag(
+            "heartbeats",
+            "Send heartbeats while a side is active, so it shows up live in the timeline",
+        ),
+        Field::number("heartbeat_interval", "Seconds between heartbeats").only_if(|config| {
+            config
+                .get("heartbeats")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        }),
+    ]
+}
+
+pub fn setup_config(config: &mut ActivityWatchConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<ActivityWatchConfig>(CONFIG_KEY, &fields())?;
+
+    match crate::config::try_get_config::<ActivityWatchConfig>(CONFIG_KEY)? {
+        Some(config) if config.heartbeat_interval == 0 => Err(String::from(
+            "activitywatch.heartbeat_interval has to be at least 1 second",
+        )),
+        _ => Ok(()),
+    }
+}
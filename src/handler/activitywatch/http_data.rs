@@ -0,0 +1,23 @@
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketRequest {
+    pub client: String,
+    #[serde(rename = "type")]
+    pub bucket_type: String,
+    pub hostname: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub timestamp: String,
+    pub duration: f64,
+    pub data: EventData,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventData {
+    pub label: String,
+    pub side_num: u8,
+}
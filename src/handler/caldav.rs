@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use log::{debug, error};
+use reqwest::header::{CONTENT_TYPE, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
+
+use crate::handler::caldav::config::{create_config, setup_config, CaldavConfig};
+use crate::handler::ical::event::{to_calendar, Event};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+
+#[derive(Debug, Default)]
+pub struct Caldav {
+    client: Client,
+    config: CaldavConfig,
+}
+
+#[async_trait]
+impl Handler for Caldav {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let event = Event::new(side, duration);
+
+        let response = self
+            .client
+            .put(format!(
+                "{}/{}.ics",
+                self.config.calendar_url.trim_end_matches('/'),
+                event.uid
+            ))
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header(CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .header(IF_NONE_MATCH, "*")
+            .body(to_calendar("", std::slice::from_ref(&event)))
+            .send()
+            .await;
+
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                error!("CalDAV error {}", e);
+                return;
+            }
+        };
+
+        match response.status() {
+            status if status.is_success() => debug!("Event {} created", event.uid),
+            StatusCode::PRECONDITION_FAILED => debug!("Event {} already exists", event.uid),
+            status => error!(
+                "CalDAV server responded with {}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            ),
+        }
+    }
+}
+
+pub async fn create_handler(setup: bool) -> Caldav {
+    let mut config = create_config();
+    let client = Client::builder().build().unwrap();
+    setup_config(&mut config, setup);
+
+    Caldav { client, config }
+}
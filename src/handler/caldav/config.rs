@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "caldav";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CaldavConfig {
+    pub calendar_url: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl<'de> Config<'de> for CaldavConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["password"]
+    }
+}
+
+pub fn create_config() -> CaldavConfig {
+    crate::config::get_config::<CaldavConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text(
+            "calendar_url",
+            "CalDAV calendar url, e.g. https://cloud.example.com/remote.php/dav/calendars/user/personal",
+        )
+        .required()
+        .validate(wizard::url),
+        Field::text("username", "CalDAV username").required(),
+        Field::text("password", "CalDAV password")
+            .secret()
+            .required(),
+    ]
+}
+
+pub fn setup_config(config: &mut CaldavConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<CaldavConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<CaldavConfig>(CONFIG_KEY);
+}
@@ -1,16 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::wizard::{self, Field};
 use crate::config::Config;
 
 const CONFIG_KEY: &str = "clockify";
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct ClockifyConfig {
     pub base_url: String,
     pub time_entries_uri: String,
     pub api_key: String,
-    pub project_id: String,
     pub workspace_id: String,
+    /// Used for sides without a `project_id`, kept from configs older than per side projects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    pub sides: Vec<ClockifySide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ClockifySide {
+    pub side_num: u8,
+    pub project_id: String,
 }
 
 impl Default for ClockifyConfig {
@@ -19,17 +30,50 @@ impl Default for ClockifyConfig {
             base_url: String::from("https://app.clockify.me"),
             time_entries_uri: String::from("/api/v1/workspaces/{workspace_id}/time-entries"),
             api_key: String::new(),
-            project_id: String::new(),
             workspace_id: String::new(),
+            project_id: None,
+            sides: vec![],
         }
     }
 }
-impl<'de> Config<'de> for ClockifyConfig {}
+impl<'de> Config<'de> for ClockifyConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["api_key"]
+    }
+}
+
+impl ClockifyConfig {
+    pub fn get_project_id(&self, side_num: &u8) -> Option<&String> {
+        self.sides
+            .iter()
+            .find(|s| s.side_num.eq(side_num))
+            .map(|s| &s.project_id)
+            .or(self.project_id.as_ref())
+    }
+}
 
 pub fn create_config() -> ClockifyConfig {
     crate::config::get_config::<ClockifyConfig>(CONFIG_KEY)
 }
 
-pub fn update_config(config: &ClockifyConfig) {
-    crate::config::update_config(CONFIG_KEY, config);
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("workspace_id", "Clockify workspace id").required(),
+        Field::text("project_id", "Clockify project id").per_side(),
+        Field::text("api_key", "Clockify API key")
+            .secret()
+            .required(),
+    ]
+}
+
+pub fn setup_config(config: &mut ClockifyConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<ClockifyConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<ClockifyConfig>(CONFIG_KEY);
 }
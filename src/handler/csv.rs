@@ -0,0 +1,300 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+
+use log::{debug, error, warn};
+
+use crate::config::expand_path;
+use crate::handler::csv::config::{create_config, setup_config, CsvConfig};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+
+const COLUMNS: [&str; 6] = ["date", "start", "end", "duration", "side_num", "label"];
+
+#[derive(Debug, Default)]
+pub struct Csv {
+    config: CsvConfig,
+}
+
+impl Csv {
+    fn get_file_path(&self, date: &DateTime<Local>) -> PathBuf {
+        let path = expand_path(&self.config.path);
+        if !self.config.monthly_rotation {
+            return path;
+        }
+
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut file_name = format!("{}-{}", stem, date.format("%Y-%m"));
+        if let Some(extension) = path.extension() {
+            file_name.push('.');
+            file_name.push_str(&extension.to_string_lossy());
+        }
+
+        path.with_file_name(file_name)
+    }
+
+    fn get_column_value(
+        &self,
+        column: &str,
+        side: &Side,
+        duration: &(DateTime<Local>, DateTime<Local>),
+    ) -> String {
+        match column {
+            "date" => duration.0.format(&self.config.date_format).to_string(),
+            "start" => duration.0.format(&self.config.time_format).to_string(),
+            "end" => duration.1.format(&self.config.time_format).to_string(),
+            "duration" => {
+                let seconds = (duration.1 - duration.0).num_seconds();
+                format!(
+                    "{}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds % 3600 / 60,
+                    seconds % 60
+                )
+            }
+            "side_num" => side.side_num.to_string(),
+            "label" => side.label.clone(),
+            _ => String::new(),
+        }
+    }
+
+    fn format_row(&self, values: &[String]) -> String {
+        let mut row = values
+            .iter()
+            .map(|value| self.escape(value))
+            .collect::<Vec<String>>()
+            .join(&self.config.delimiter.to_string());
+        row.push('\n');
+
+        row
+    }
+
+    fn escape(&self, value: &str) -> String {
+        if value.contains(self.config.delimiter)
+            || value.contains('"')
+            || value.contains('\n')
+            || value.contains('\r')
+        {
+            return format!("\"{}\"", value.replace('"', "\"\""));
+        }
+
+        value.to_string()
+    }
+}
+
+#[async_trait]
+impl Handler for Csv {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let path = self.get_file_path(&duration.0);
+        let write_header = fs::metadata(&path).map(|m| m.len() == 0).unwrap_or(true);
+
+        let mut content = String::new();
+        if write_header {
+            content.push_str(&self.format_row(&self.config.columns));
+        }
+        let values = self
+            .config
+            .columns
+            .iter()
+            .map(|column| self.get_column_value(column, side, duration))
+            .collect::<Vec<String>>();
+        content.push_str(&self.format_row(&values));
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(content.as_bytes()));
+
+        if let Err(e) = result {
+            error!("Could not write to {}: {}", path.display(), e);
+            return;
+        }
+
+        debug!("Row written to {}", path.display());
+    }
+}
+
+pub async fn create_handler(setup: bool) -> Csv {
+    let mut config = create_config();
+    setup_config(&mut config, setup);
+
+    if let Err(e) = config.check_formats() {
+        error!("Invalid config: {}", e);
+        process::exit(1);
+    }
+
+    for column in config.columns.iter() {
+        if !COLUMNS.contains(&column.as_str()) {
+            warn!(
+                "Unknown csv column \"{}\", available columns: {}",
+                column,
+                COLUMNS.join(", ")
+            );
+        }
+    }
+
+    Csv { config }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn csv(config: CsvConfig) -> Csv {
+        Csv { config }
+    }
+
+    fn side(side_num: u8, label: &str) -> Side {
+        Side {
+            side_num,
+            label: label.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn escape_leaves_plain_values() {
+        let csv = csv(CsvConfig::default());
+
+        assert_eq!(csv.escape("Meetings"), "Meetings");
+    }
+
+    #[test]
+    fn escape_quotes_delimiter_quotes_and_newlines() {
+        let csv = csv(CsvConfig::default());
+
+        assert_eq!(csv.escape("a,b"), "\"a,b\"");
+        assert_eq!(csv.escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv.escape("a\nb"), "\"a\nb\"");
+        assert_eq!(csv.escape("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn escape_uses_configured_delimiter() {
+        let csv = csv(CsvConfig {
+            delimiter: ';',
+            ..Default::default()
+        });
+
+        assert_eq!(csv.escape("a,b"), "a,b");
+        assert_eq!(csv.escape("a;b"), "\"a;b\"");
+        assert_eq!(
+            csv.format_row(&[String::from("a"), String::from("b;c")]),
+            "a;\"b;c\"\n"
+        );
+    }
+
+    #[test]
+    fn row_has_every_column() {
+        let csv = csv(CsvConfig::default());
+        let start = Local.with_ymd_and_hms(2024, 5, 6, 9, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2024, 5, 6, 10, 2, 3).unwrap();
+
+        let values: Vec<String> = COLUMNS
+            .iter()
+            .map(|column| csv.get_column_value(column, &side(3, "Code, review"), &(start, end)))
+            .collect();
+
+        assert_eq!(
+            csv.format_row(&values),
+            "2024-05-06,09:00:00,10:02:03,1:02:03,3,\"Code, review\"\n"
+        );
+    }
+
+    #[test]
+    fn file_path_without_rotation() {
+        let csv = csv(CsvConfig {
+            path: String::from("/tmp/timesheet.csv"),
+            ..Default::default()
+        });
+        let date = Local.with_ymd_and_hms(2024, 5, 6, 9, 0, 0).unwrap();
+
+        assert_eq!(
+            csv.get_file_path(&date),
+            PathBuf::from("/tmp/timesheet.csv")
+        );
+    }
+
+    #[test]
+    fn file_path_with_monthly_rotation() {
+        let csv = csv(CsvConfig {
+            path: String::from("/tmp/timesheet.csv"),
+            monthly_rotation: true,
+            ..Default::default()
+        });
+        let date = Local.with_ymd_and_hms(2024, 5, 6, 9, 0, 0).unwrap();
+
+        assert_eq!(
+            csv.get_file_path(&date),
+            PathBuf::from("/tmp/timesheet-2024-05.csv")
+        );
+    }
+
+    #[test]
+    fn file_path_with_rotation_and_no_extension() {
+        let csv = csv(CsvConfig {
+            path: String::from("timesheet"),
+            monthly_rotation: true,
+            ..Default::default()
+        });
+        let date = Local.with_ymd_and_hms(2024, 12, 31, 23, 0, 0).unwrap();
+
+        assert_eq!(csv.get_file_path(&date), PathBuf::from("timesheet-2024-12"));
+    }
+
+    #[test]
+    fn file_path_expands_home() {
+        let csv = csv(CsvConfig {
+            path: String::from("~/timesheet.csv"),
+            monthly_rotation: true,
+            ..Default::default()
+        });
+        let date = Local.with_ymd_and_hms(2024, 5, 6, 9, 0, 0).unwrap();
+
+        assert_eq!(
+            csv.get_file_path(&date),
+            expand_path("~/timesheet-2024-05.csv")
+        );
+    }
+
+    #[test]
+    fn invalid_formats_are_rejected() {
+        let config = CsvConfig {
+            time_format: String::from("%H:%Q"),
+            ..Default::default()
+        };
+
+        assert!(CsvConfig::default().check_formats().is_ok());
+        assert_eq!(
+            config.check_formats(),
+            Err(String::from(
+                "csv.time_format \"%H:%Q\" is not a valid format"
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_format_is_reported_at_its_value() {
+        let contents = "[csv]\ndate_format = \"%Q\"\n";
+        let document = toml_edit::ImDocument::parse(contents).unwrap();
+
+        let diagnostic = config::check_formats(&["csv"], &document["csv"]).unwrap();
+
+        assert_eq!(
+            diagnostic.message,
+            "[csv] date_format is not a valid format"
+        );
+        assert_eq!(diagnostic.span, Some(20..24));
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::format::{Item as FormatItem, StrftimeItems};
+use serde::{Deserialize, Serialize};
+use toml_edit::Item;
+
+use crate::config::diagnostic::Diagnostic;
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "csv";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CsvConfig {
+    pub path: String,
+    pub columns: Vec<String>,
+    pub delimiter: char,
+    pub date_format: String,
+    pub time_format: String,
+    pub monthly_rotation: bool,
+}
+
+impl Default for CsvConfig {
+    fn default() -> Self {
+        CsvConfig {
+            path: String::from("timesheet.csv"),
+            columns: vec![
+                String::from("date"),
+                String::from("start"),
+                String::from("end"),
+                String::from("duration"),
+                String::from("side_num"),
+                String::from("label"),
+            ],
+            delimiter: ',',
+            date_format: String::from("%Y-%m-%d"),
+            time_format: String::from("%H:%M:%S"),
+            monthly_rotation: false,
+        }
+    }
+}
+impl<'de> Config<'de> for CsvConfig {}
+
+const FORMATS: [&str; 2] = ["date_format", "time_format"];
+
+impl CsvConfig {
+    /// chrono panics when it formats with an invalid format, so they are checked up front
+    pub fn check_formats(&self) -> Result<(), String> {
+        for (name, format) in FORMATS.iter().zip([&self.date_format, &self.time_format]) {
+            if !is_valid_format(format) {
+                return Err(format!("csv.{} \"{}\" is not a valid format", name, format));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_valid_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, FormatItem::Error))
+}
+
+/// Reports a date or time format chrono can't use, `item` is the `[csv]` section at `path`
+pub fn check_formats(path: &[&str], item: &Item) -> Option<Diagnostic> {
+    FORMATS.iter().find_map(|name| {
+        let format = item.get(name)?;
+        if is_valid_format(format.as_str()?) {
+            return None;
+        }
+
+        Some(Diagnostic::error(
+            format!("[{}] {} is not a valid format", path.join("."), name),
+            format.span(),
+        ))
+    })
+}
+
+pub fn create_config() -> CsvConfig {
+    crate::config::get_config::<CsvConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![Field::text("path", "csv file path")]
+}
+
+pub fn setup_config(config: &mut CsvConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<CsvConfig>(CONFIG_KEY, &fields())?;
+
+    match crate::config::try_get_config::<CsvConfig>(CONFIG_KEY)? {
+        Some(config) => config.check_formats(),
+        None => Ok(()),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::handler::example::config::{create_config, update_config, ExampleConfig};
+use crate::handler::example::config::{create_config, setup_config, ExampleConfig};
 use crate::tracker::config::{Handler, Side};
 use async_trait::async_trait;
 use chrono::{DateTime, Local};
@@ -43,29 +43,7 @@ impl Handler for Example {
 pub async fn create_handler(setup: bool) -> Example {
     let mut config = create_config();
     let client = Client::builder().build().unwrap();
-    update_vendor_config(&mut config, setup);
+    setup_config(&mut config, setup);
 
     return Example { client, config };
 }
-
-fn update_vendor_config(config: &mut ExampleConfig, setup: bool) {
-    if setup || config.api_key.is_empty() {
-        let mut api_key = String::new();
-        let mut message =
-            String::from_utf8("Provide your Example api_key".as_bytes().to_vec()).unwrap();
-        if config.api_key.is_empty() {
-            message.push_str("\n leave blank to skip");
-        }
-        info!("{message}");
-
-        std::io::stdin()
-            .read_line(&mut api_key)
-            .expect("Please provide api_key");
-        api_key = api_key.trim().to_string();
-
-        if !api_key.is_empty() {
-            config.api_key = api_key;
-            update_config(&config);
-        }
-    }
-}
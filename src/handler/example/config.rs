@@ -1,7 +1,9 @@
+use crate::config::wizard::{self, Field};
 use crate::config::Config;
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct ExampleConfig {
     pub base_url: String,
     pub api_key: String,
@@ -16,7 +18,11 @@ impl Default for ExampleConfig {
     }
 }
 
-impl<'de> Config<'de> for ExampleConfig {}
+impl<'de> Config<'de> for ExampleConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["api_key"]
+    }
+}
 
 const CONFIG_KEY: &str = "example";
 
@@ -24,6 +30,29 @@ pub fn create_config() -> ExampleConfig {
     crate::config::get_config::<ExampleConfig>(CONFIG_KEY)
 }
 
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("base_url", "Example API url")
+            .default("https://api.example.com")
+            .validate(wizard::url),
+        Field::text("api_key", "Example API key")
+            .secret()
+            .required(),
+    ]
+}
+
+pub fn setup_config(config: &mut ExampleConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<ExampleConfig>(CONFIG_KEY, &fields())
+}
+
 pub fn update_config(config: &ExampleConfig) {
     crate::config::update_config(CONFIG_KEY, config);
 }
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<ExampleConfig>(CONFIG_KEY);
+}
@@ -4,10 +4,9 @@ pub mod http_data;
 use async_trait::async_trait;
 use chrono::{DateTime, Local};
 use http_data::*;
-use log::{debug, info};
+use log::debug;
 use reqwest::Client;
 use reqwest_cookie_store::CookieStoreMutex;
-use rpassword::prompt_password;
 use std::string::String;
 use std::sync::Arc;
 
@@ -16,7 +15,7 @@ use crate::{
     tracker::config::{Handler, Side},
 };
 
-use self::config::{create_config, HackaruConfig};
+use self::config::{create_config, setup_config, HackaruConfig};
 #[derive(Debug, Default)]
 pub struct Hackaru {
     client: Client,
@@ -29,7 +28,7 @@ impl Handler for Hackaru {
         let activity_start = ActivityStartRequest {
             activity: ActivityStartData {
                 description: side.label.clone(),
-                project_id: self.config.project_id,
+                project_id: self.config.get_project_id(&side.side_num),
                 started_at: duration.0.to_rfc3339(),
             },
         };
@@ -76,7 +75,7 @@ pub async fn create_handler(setup: bool) -> Hackaru {
     let mut config = create_config();
     let cookie_store = create_cookie_store(&config);
     let client = create_client(&cookie_store);
-    setup_vendor_config(setup, &mut config).await;
+    setup_config(&mut config, setup);
 
     if !has_cookies(&cookie_store) {
         auth(&client, &config).await;
@@ -110,91 +109,6 @@ fn create_client(cookie_store: &Arc<CookieStoreMutex>) -> Client {
         .unwrap()
 }
 
-async fn setup_vendor_config(setup: bool, config: &mut HackaruConfig) {
-    if setup || config.hackaru_url.is_empty() {
-        let mut hackaru_url = String::new();
-        let mut message =
-            String::from_utf8("Provide your hackaru url".as_bytes().to_vec()).unwrap();
-        if config.project_id != 0 {
-            message.push_str(
-                format!("\ncurrent value {}, leave blank to skip", config.project_id).as_str(),
-            );
-        }
-        info!("{message}");
-
-        std::io::stdin()
-            .read_line(&mut hackaru_url)
-            .expect("Please provide url");
-
-        hackaru_url = hackaru_url.trim().to_string();
-
-        if !hackaru_url.is_empty() {
-            config.hackaru_url = hackaru_url;
-            update_config(&config);
-        }
-    }
-
-    if setup || config.project_id == 0 {
-        let mut project_id = String::new();
-        let mut message =
-            String::from_utf8("Provide your hackaru project id".as_bytes().to_vec()).unwrap();
-        if config.project_id != 0 {
-            message.push_str(
-                format!("\ncurrent value {}, leave blank to skip", config.project_id).as_str(),
-            );
-        }
-        info!("{message}");
-
-        std::io::stdin()
-            .read_line(&mut project_id)
-            .expect("Please provide project_id");
-
-        project_id = project_id.trim().to_string();
-
-        if !project_id.is_empty() {
-            config.project_id = project_id.parse::<u64>().unwrap();
-            update_config(&config);
-        }
-    }
-
-    if setup || config.email.is_empty() {
-        let mut email = String::new();
-        let mut message =
-            String::from_utf8("Provide your hackaru email".as_bytes().to_vec()).unwrap();
-        if !config.email.is_empty() {
-            message.push_str(
-                format!("\ncurrent value {}, leave blank to skip", config.email).as_str(),
-            );
-        }
-        info!("{message}");
-
-        std::io::stdin()
-            .read_line(&mut email)
-            .expect("Please provide email");
-
-        email = email.trim().to_string();
-
-        if !email.is_empty() {
-            config.email = email;
-            update_config(&config);
-        }
-    }
-
-    if setup || config.password.is_empty() {
-        let mut message =
-            String::from_utf8("Provide your hackaru password".as_bytes().to_vec()).unwrap();
-        if !config.password.is_empty() {
-            message.push_str("\nleave blank to use current value");
-        }
-        let password: String = (*prompt_password(message).unwrap().trim()).to_string();
-
-        if !password.is_empty() {
-            config.password = password;
-            update_config(&config);
-        }
-    }
-}
-
 async fn auth(client: &Client, config: &HackaruConfig) {
     let login = LoginRequest {
         user: UserRequest {
@@ -1,18 +1,29 @@
 use reqwest_cookie_store::CookieStore;
 use serde::{Deserialize, Serialize};
 
+use crate::config::wizard::{self, Field};
 use crate::config::Config;
 
 const CONFIG_KEY: &str = "hackaru";
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct HackaruConfig {
     pub hackaru_url: String,
     pub activities_rel_url: String,
     pub email: String,
-    pub project_id: u64,
     pub cookies: String,
     pub password: String,
+    /// Used for sides without a `project_id`, kept from configs older than per side projects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<u64>,
+    pub sides: Vec<HackaruSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HackaruSide {
+    pub side_num: u8,
+    pub project_id: u64,
 }
 
 impl Default for HackaruConfig {
@@ -21,15 +32,28 @@ impl Default for HackaruConfig {
             hackaru_url: String::new(),
             activities_rel_url: String::from("v1/activities"),
             email: String::new(),
-            project_id: 0,
             cookies: String::new(),
             password: String::new(),
+            project_id: None,
+            sides: vec![],
         }
     }
 }
-impl<'de> Config<'de> for HackaruConfig {}
+impl<'de> Config<'de> for HackaruConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["password", "cookies"]
+    }
+}
 
 impl HackaruConfig {
+    pub fn get_project_id(&self, side_num: &u8) -> Option<u64> {
+        self.sides
+            .iter()
+            .find(|s| s.side_num.eq(side_num))
+            .map(|s| s.project_id)
+            .or(self.project_id)
+    }
+
     pub fn get_cookie_store(&self) -> CookieStore {
         let cookies_str = self.cookies.as_str();
         if cookies_str.is_empty() {
@@ -45,6 +69,33 @@ pub fn create_config() -> HackaruConfig {
     crate::config::get_config::<HackaruConfig>(CONFIG_KEY)
 }
 
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("hackaru_url", "Hackaru url")
+            .required()
+            .validate(wizard::url),
+        Field::number("project_id", "Hackaru project id").per_side(),
+        Field::text("email", "Hackaru email")
+            .required()
+            .validate(wizard::email),
+        Field::text("password", "Hackaru password")
+            .secret()
+            .required(),
+    ]
+}
+
+pub fn setup_config(config: &mut HackaruConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<HackaruConfig>(CONFIG_KEY, &fields())
+}
+
 pub fn update_config(config: &HackaruConfig) {
     crate::config::update_config(CONFIG_KEY, config);
 }
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<HackaruConfig>(CONFIG_KEY);
+}
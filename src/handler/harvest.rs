@@ -0,0 +1,257 @@
+pub mod config;
+pub mod http_data;
+
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use http_data::*;
+use log::{debug, error};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, RequestBuilder};
+use simplelog::info;
+
+use crate::handler::harvest::config::{create_config, setup_config, HarvestConfig, HarvestSide};
+use crate::tracker::config::{Handler, Side};
+
+const TIME_FORMAT: &str = "%-I:%M%P";
+
+#[derive(Debug, Default)]
+pub struct Harvest {
+    client: Client,
+    config: HarvestConfig,
+    running_entry_id: Mutex<Option<u64>>,
+}
+
+impl Harvest {
+    fn request(&self, request_builder: RequestBuilder) -> RequestBuilder {
+        request_builder
+            .bearer_auth(&self.config.access_token)
+            .header("Harvest-Account-Id", &self.config.account_id)
+            .header(CONTENT_TYPE, "application/json")
+    }
+
+    fn time_entries_url(&self) -> String {
+        format!(
+            "{}/v2/time_entries",
+            self.config.base_url.trim_end_matches('/')
+        )
+    }
+
+    fn find_side(&self, side: &Side) -> Option<&HarvestSide> {
+        let harvest_side = self.config.find_side(&side.side_num);
+        if harvest_side.is_none() {
+            info!(
+                "No Harvest project configured for side {}, run with --setup to set one",
+                side
+            );
+        }
+
+        harvest_side
+    }
+
+    async fn create_time_entry(&self, time_entry: &TimeEntryRequest) -> Option<u64> {
+        let response = self
+            .request(self.client.post(self.time_entries_url()))
+            .json(time_entry)
+            .send()
+            .await;
+
+        let response = match response {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                error!(
+                    "Harvest responded with {}: {}",
+                    response.status(),
+                    response.text().await.unwrap_or_default()
+                );
+                return None;
+            }
+            Err(e) => {
+                error!("Harvest error {}", e);
+                return None;
+            }
+        };
+
+        match response.json::<TimeEntryResponse>().await {
+            Ok(time_entry) => Some(time_entry.id),
+            Err(e) => {
+                error!("Could not read Harvest response: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn stop_timer(&self) {
+        let id = match self.running_entry_id.lock().unwrap().take() {
+            Some(id) => id,
+            None => return,
+        };
+
+        let response = self
+            .request(
+                self.client
+                    .patch(format!("{}/{}/stop", self.time_entries_url(), id)),
+            )
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => debug!("Timer {} stopped", id),
+            Ok(response) => error!(
+                "Harvest responded with {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ),
+            Err(e) => error!("Harvest error {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for Harvest {
+    async fn start(&self, side: &Side, start: &DateTime<Local>) {
+        if !self.config.timer {
+            return;
+        }
+
+        let harvest_side = match self.find_side(side) {
+            Some(harvest_side) => harvest_side,
+            None => return,
+        };
+
+        let time_entry = TimeEntryRequest {
+            project_id: harvest_side.project_id,
+            task_id: harvest_side.task_id,
+            spent_date: start.format("%Y-%m-%d").to_string(),
+            started_time: None,
+            ended_time: None,
+            notes: side.label.clone(),
+        };
+
+        let id = self.create_time_entry(&time_entry).await;
+        debug!("Timer started: {:?}", id);
+        *self.running_entry_id.lock().unwrap() = id;
+    }
+
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        if self.config.timer {
+            self.stop_timer().await;
+            return;
+        }
+
+        let harvest_side = match self.find_side(side) {
+            Some(harvest_side) => harvest_side,
+            None => return,
+        };
+
+        for (start, end) in split_days(duration) {
+            let time_entry = TimeEntryRequest {
+                project_id: harvest_side.project_id,
+                task_id: harvest_side.task_id,
+                spent_date: start.format("%Y-%m-%d").to_string(),
+                started_time: Some(start.format(TIME_FORMAT).to_string()),
+                ended_time: Some(end.format(TIME_FORMAT).to_string()),
+                notes: side.label.clone(),
+            };
+
+            if let Some(id) = self.create_time_entry(&time_entry).await {
+                debug!("Time entry {} created", id);
+            }
+        }
+    }
+}
+
+/// Harvest times are within the `spent_date`, so an interval that goes past midnight is split
+/// into one per day, each of them but the last ends at 23:59
+fn split_days(
+    duration: &(DateTime<Local>, DateTime<Local>),
+) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+    let mut days = vec![];
+    let mut start = duration.0;
+
+    while start.date_naive() < duration.1.date_naive() {
+        let end_of_day = start
+            .date_naive()
+            .and_hms_opt(23, 59, 0)
+            .and_then(|end| end.and_local_timezone(Local).earliest())
+            .unwrap_or(start)
+            .max(start);
+        days.push((start, end_of_day));
+
+        start = match start
+            .date_naive()
+            .succ_opt()
+            .and_then(|day| day.and_hms_opt(0, 0, 0))
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+        {
+            Some(midnight) => midnight,
+            None => break,
+        };
+    }
+    days.push((start, duration.1));
+
+    days
+}
+
+pub async fn create_handler(setup: bool) -> Harvest {
+    let mut config = create_config();
+    let client = Client::builder()
+        .user_agent("timeular-reader (https://github.com/krzysztof-ciszewski/timeular-reader)")
+        .build()
+        .unwrap();
+    setup_config(&mut config, setup);
+
+    Harvest {
+        client,
+        config,
+        running_entry_id: Mutex::new(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 5, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn interval_within_a_day_is_kept() {
+        let duration = (time(6, 9, 0), time(6, 10, 30));
+
+        assert_eq!(split_days(&duration), vec![duration]);
+    }
+
+    #[test]
+    fn interval_past_midnight_is_split() {
+        let duration = (time(6, 23, 0), time(7, 1, 15));
+
+        assert_eq!(
+            split_days(&duration),
+            vec![
+                (time(6, 23, 0), time(6, 23, 59)),
+                (time(7, 0, 0), time(7, 1, 15))
+            ]
+        );
+    }
+
+    #[test]
+    fn interval_over_several_days_is_split_per_day() {
+        let duration = (time(6, 22, 0), time(8, 2, 0));
+
+        assert_eq!(
+            split_days(&duration),
+            vec![
+                (time(6, 22, 0), time(6, 23, 59)),
+                (time(7, 0, 0), time(7, 23, 59)),
+                (time(8, 0, 0), time(8, 2, 0))
+            ]
+        );
+    }
+}
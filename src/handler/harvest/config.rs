@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "harvest";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct HarvestConfig {
+    pub base_url: String,
+    pub account_id: String,
+    pub access_token: String,
+    pub timer: bool,
+    pub sides: Vec<HarvestSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HarvestSide {
+    pub side_num: u8,
+    pub project_id: u64,
+    pub task_id: u64,
+}
+
+impl Default for HarvestConfig {
+    fn default() -> Self {
+        HarvestConfig {
+            base_url: String::from("https://api.harvestapp.com"),
+            account_id: String::new(),
+            access_token: String::new(),
+            timer: false,
+            sides: vec![],
+        }
+    }
+}
+impl<'de> Config<'de> for HarvestConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["access_token"]
+    }
+}
+
+impl HarvestConfig {
+    pub fn find_side(&self, side_num: &u8) -> Option<&HarvestSide> {
+        self.sides.iter().find(|s| s.side_num.eq(side_num))
+    }
+}
+
+pub fn create_config() -> HarvestConfig {
+    crate::config::get_config::<HarvestConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("account_id", "Harvest account id").required(),
+        Field::text("access_token", "Harvest personal access token")
+            .secret()
+            .required(),
+        Field::number("project_id", "Harvest project id")
+            .per_side()
+            .required(),
+        Field::number("task_id", "Harvest task id")
+            .per_side()
+            .required(),
+    ]
+}
+
+pub fn setup_config(config: &mut HarvestConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<HarvestConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<HarvestConfig>(CONFIG_KEY);
+}
@@ -0,0 +1,19 @@
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntryRequest {
+    pub project_id: u64,
+    pub task_id: u64,
+    pub spent_date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_time: Option<String>,
+    pub notes: String,
+}
+
+#[derive(Deserialize)]
+pub struct TimeEntryResponse {
+    pub id: u64,
+}
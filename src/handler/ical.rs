@@ -0,0 +1,81 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use log::{debug, error};
+
+use crate::config::expand_path;
+use crate::handler::ical::config::{create_config, setup_config, IcalConfig};
+use crate::handler::ical::event::{to_calendar, Event, CALENDAR_END};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+pub mod event;
+
+#[derive(Debug, Default)]
+pub struct Ical {
+    config: IcalConfig,
+    path: PathBuf,
+}
+
+impl Ical {
+    fn add_event(&self, event: &Event) -> io::Result<bool> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        if content.contains(&format!("UID:{}", event.uid)) {
+            return Ok(false);
+        }
+
+        let content = if content.trim().is_empty() {
+            to_calendar(&self.config.calendar_name, std::slice::from_ref(event))
+        } else {
+            let idx = content.rfind(CALENDAR_END).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "missing END:VCALENDAR")
+            })?;
+            format!(
+                "{}{}{}",
+                &content[..idx],
+                event.to_vevent(),
+                &content[idx..]
+            )
+        };
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write to a temporary file first so clients reading the calendar never see it half written
+        let tmp_path = self.path.with_extension("ics.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &self.path)?;
+
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl Handler for Ical {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let event = Event::new(side, duration);
+
+        match self.add_event(&event) {
+            Ok(true) => debug!("Event {} added to {}", event.uid, self.path.display()),
+            Ok(false) => debug!("Event {} already exists", event.uid),
+            Err(e) => error!("Could not write to {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+pub async fn create_handler(setup: bool) -> Ical {
+    let mut config = create_config();
+    setup_config(&mut config, setup);
+    let path = expand_path(&config.path);
+
+    Ical { config, path }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "ical";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct IcalConfig {
+    pub path: String,
+    pub calendar_name: String,
+}
+
+impl Default for IcalConfig {
+    fn default() -> Self {
+        IcalConfig {
+            path: String::from("timeular.ics"),
+            calendar_name: String::from("Timeular"),
+        }
+    }
+}
+impl<'de> Config<'de> for IcalConfig {}
+
+pub fn create_config() -> IcalConfig {
+    crate::config::get_config::<IcalConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![Field::text("path", "ics file path")]
+}
+
+pub fn setup_config(config: &mut IcalConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<IcalConfig>(CONFIG_KEY, &fields())
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Local, Utc};
+
+use crate::tracker::config::Side;
+
+const DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const CRLF: &str = "\r\n";
+pub const CALENDAR_END: &str = "END:VCALENDAR";
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub uid: String,
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Event {
+    pub fn new(side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) -> Self {
+        let start = duration.0.with_timezone(&Utc);
+
+        Event {
+            uid: format!(
+                "{}-{}@timeular-reader",
+                start.format(DATE_FORMAT),
+                side.side_num
+            ),
+            summary: side.label.clone(),
+            start,
+            end: duration.1.with_timezone(&Utc),
+        }
+    }
+
+    pub fn to_vevent(&self) -> String {
+        [
+            String::from("BEGIN:VEVENT"),
+            format!("UID:{}", self.uid),
+            format!("DTSTAMP:{}", Utc::now().format(DATE_FORMAT)),
+            format!("DTSTART:{}", self.start.format(DATE_FORMAT)),
+            format!("DTEND:{}", self.end.format(DATE_FORMAT)),
+            format!("SUMMARY:{}", escape(&self.summary)),
+            String::from("END:VEVENT"),
+        ]
+        .iter()
+        .map(|line| fold(line))
+        .collect()
+    }
+}
+
+pub fn calendar_start(name: &str) -> String {
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//timeular-reader//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+    ];
+    if !name.is_empty() {
+        lines.push(format!("X-WR-CALNAME:{}", escape(name)));
+    }
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+pub fn to_calendar(name: &str, events: &[Event]) -> String {
+    let mut calendar = calendar_start(name);
+    for event in events {
+        calendar.push_str(&event.to_vevent());
+    }
+    calendar.push_str(CALENDAR_END);
+    calendar.push_str(CRLF);
+
+    calendar
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds the content line so no line is longer than 75 octets, as required by RFC 5545.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_len = 0;
+    for c in line.chars() {
+        if line_len + c.len_utf8() > 75 {
+            folded.push_str(CRLF);
+            folded.push(' ');
+            line_len = 1;
+        }
+        folded.push(c);
+        line_len += c.len_utf8();
+    }
+    folded.push_str(CRLF);
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(escape("Meetings"), "Meetings");
+        assert_eq!(escape("a,b;c\\d\ne"), r"a\,b\;c\\d\ne");
+    }
+
+    #[test]
+    fn short_line_is_not_folded() {
+        assert_eq!(fold("SUMMARY:Meetings"), "SUMMARY:Meetings\r\n");
+    }
+
+    #[test]
+    fn long_line_is_folded_at_75_octets() {
+        let line = format!("SUMMARY:{}", "a".repeat(100));
+
+        let folded = fold(&line);
+
+        let lines: Vec<&str> = folded.trim_end_matches(CRLF).split(CRLF).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(format!("{}{}", lines[0], &lines[1][1..]), line);
+    }
+
+    #[test]
+    fn fold_does_not_split_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(50));
+
+        let folded = fold(&line);
+
+        for line in folded.split(CRLF) {
+            assert!(line.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}{}", line, CRLF));
+    }
+
+    #[test]
+    fn calendar_with_event() {
+        let event = Event {
+            uid: String::from("uid-1"),
+            summary: String::from("Code, review"),
+            start: Utc.with_ymd_and_hms(2024, 5, 6, 9, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 5, 6, 10, 0, 0).unwrap(),
+        };
+
+        let calendar = to_calendar("Work", &[event]);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.contains("X-WR-CALNAME:Work\r\n"));
+        assert!(calendar.contains("DTSTART:20240506T090000Z\r\nDTEND:20240506T100000Z\r\n"));
+        assert!(calendar.contains("SUMMARY:Code\\, review\r\n"));
+        assert!(calendar.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+}
@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use log::{debug, error};
+use reqwest::Client;
+use serde_json::json;
+use simplelog::info;
+
+use crate::handler::jira::config::{create_config, render_worklog_uri, setup_config, JiraConfig};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%z";
+
+#[derive(Debug, Default)]
+pub struct Jira {
+    client: Client,
+    config: JiraConfig,
+}
+
+impl Jira {
+    fn get_issue_key(&self, side: &Side) -> Option<String> {
+        match self.config.find_side(&side.side_num) {
+            Some(jira_side) if !jira_side.issue_key.is_empty() => Some(jira_side.issue_key.clone()),
+            _ => extract_issue_key(&side.label),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for Jira {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let issue_key = match self.get_issue_key(side) {
+            Some(issue_key) => issue_key,
+            None => {
+                info!(
+                    "No Jira issue for side {}, set issue_key or put it in the label",
+                    side
+                );
+                return;
+            }
+        };
+
+        let seconds = (duration.1 - duration.0).num_seconds();
+        if seconds < 60 {
+            info!("Jira doesn't accept worklogs shorter than a minute, skipping");
+            return;
+        }
+
+        let worklog_uri = match render_worklog_uri(&self.config.worklog_uri, issue_key) {
+            Ok(worklog_uri) => worklog_uri,
+            Err(e) => {
+                error!("Invalid jira.worklog_uri: {}", e);
+                return;
+            }
+        };
+
+        let request_builder = self
+            .client
+            .post(format!(
+                "{}/{}",
+                self.config.base_url.trim_end_matches('/'),
+                worklog_uri
+            ))
+            .json(&json!({
+                "started": duration.0.format(DATE_FORMAT).to_string(),
+                "timeSpentSeconds": seconds,
+            }));
+
+        let request_builder = if self.config.email.is_empty() {
+            request_builder.bearer_auth(&self.config.personal_access_token)
+        } else {
+            request_builder.basic_auth(&self.config.email, Some(&self.config.api_token))
+        };
+
+        match request_builder.send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!("Response: {}", response.text().await.unwrap_or_default())
+            }
+            Ok(response) => error!(
+                "Jira responded with {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ),
+            Err(e) => error!("Jira error {}", e),
+        }
+    }
+}
+
+/// Finds the first issue key like `PROJ-123` in the label
+fn extract_issue_key(label: &str) -> Option<String> {
+    label
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .find(|word| match word.rsplit_once('-') {
+            Some((project, number)) => {
+                project.starts_with(|c: char| c.is_ascii_uppercase())
+                    && project
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                    && !number.is_empty()
+                    && number.chars().all(|c| c.is_ascii_digit())
+            }
+            None => false,
+        })
+        .map(String::from)
+}
+
+pub async fn create_handler(setup: bool) -> Jira {
+    let mut config = create_config();
+    let client = Client::builder().build().unwrap();
+    setup_config(&mut config, setup);
+
+    Jira { client, config }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_key_is_found_in_label() {
+        assert_eq!(
+            extract_issue_key("PROJ-123 Review"),
+            Some(String::from("PROJ-123"))
+        );
+        assert_eq!(
+            extract_issue_key("Review (AB2_X-7)"),
+            Some(String::from("AB2_X-7"))
+        );
+        assert_eq!(
+            extract_issue_key("fix proj-1, then PROJ-2"),
+            Some(String::from("PROJ-2"))
+        );
+    }
+
+    #[test]
+    fn label_without_issue_key() {
+        assert_eq!(extract_issue_key("Meetings"), None);
+        assert_eq!(extract_issue_key("PROJ- 12"), None);
+        assert_eq!(extract_issue_key("PROJ-12a"), None);
+        assert_eq!(extract_issue_key("1PROJ-12"), None);
+        assert_eq!(extract_issue_key(""), None);
+    }
+
+    #[test]
+    fn worklog_uri_is_rendered() {
+        assert_eq!(
+            render_worklog_uri(
+                "/rest/api/2/issue/{issue_key}/worklog/",
+                String::from("PROJ-1")
+            )
+            .unwrap(),
+            "rest/api/2/issue/PROJ-1/worklog"
+        );
+    }
+
+    #[test]
+    fn invalid_worklog_uri_is_an_error() {
+        assert!(render_worklog_uri("rest/{issue}/worklog", String::from("PROJ-1")).is_err());
+        assert!(render_worklog_uri("rest/{issue_key/worklog", String::from("PROJ-1")).is_err());
+    }
+}
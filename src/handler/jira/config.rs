@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use tinytemplate::TinyTemplate;
+use toml::{value::Table, Value};
+use toml_edit::Item;
+
+use crate::config::diagnostic::Diagnostic;
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "jira";
+
+#[derive(Serialize)]
+pub struct Context {
+    pub issue_key: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct JiraConfig {
+    pub base_url: String,
+    pub worklog_uri: String,
+    pub email: String,
+    pub api_token: String,
+    pub personal_access_token: String,
+    pub sides: Vec<JiraSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JiraSide {
+    pub side_num: u8,
+    pub issue_key: String,
+}
+
+impl Default for JiraConfig {
+    fn default() -> Self {
+        JiraConfig {
+            base_url: String::new(),
+            worklog_uri: String::from("rest/api/2/issue/{issue_key}/worklog"),
+            email: String::new(),
+            api_token: String::new(),
+            personal_access_token: String::new(),
+            sides: vec![],
+        }
+    }
+}
+impl<'de> Config<'de> for JiraConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["api_token", "personal_access_token"]
+    }
+}
+
+impl JiraConfig {
+    pub fn find_side(&self, side_num: &u8) -> Option<&JiraSide> {
+        self.sides.iter().find(|s| s.side_num.eq(side_num))
+    }
+}
+
+pub fn render_worklog_uri(
+    worklog_uri: &str,
+    issue_key: String,
+) -> Result<String, tinytemplate::error::Error> {
+    let mut tt = TinyTemplate::new();
+    tt.add_template("url", worklog_uri.trim_matches('/'))?;
+
+    tt.render("url", &Context { issue_key })
+}
+
+/// Reports a `worklog_uri` that can't be rendered, `item` is the `[jira]` section at `path`
+pub fn check_worklog_uri(path: &[&str], item: &Item) -> Option<Diagnostic> {
+    let worklog_uri = item.get("worklog_uri")?;
+    let error = render_worklog_uri(worklog_uri.as_str()?, String::from("PROJ-1")).err()?;
+
+    Some(Diagnostic::error(
+        format!(
+            "[{}] worklog_uri is not a valid template: {}",
+            path.join("."),
+            error
+        ),
+        worklog_uri.span(),
+    ))
+}
+
+pub fn create_config() -> JiraConfig {
+    crate::config::get_config::<JiraConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("base_url", "Jira url, e.g. https://your-domain.atlassian.net")
+            .required()
+            .validate(wizard::url),
+        Field::text(
+            "email",
+            "Jira Cloud email, leave it unset to use a personal access token for Jira Server/Data Center",
+        )
+        .validate(wizard::email),
+        Field::text("api_token", "Jira API token")
+            .secret()
+            .required()
+            .only_if(has_email),
+        Field::text("personal_access_token", "Jira personal access token")
+            .secret()
+            .required()
+            .only_if(|config| !has_email(config)),
+        Field::text(
+            "issue_key",
+            "Jira issue key, it's taken from the label when it's not set",
+        )
+        .per_side()
+        .validate(|input| match super::extract_issue_key(input) {
+            Some(issue_key) if issue_key == input => Ok(()),
+            _ => Err(String::from("Please provide an issue key like PROJ-123")),
+        }),
+    ]
+}
+
+fn has_email(config: &Table) -> bool {
+    config
+        .get("email")
+        .and_then(Value::as_str)
+        .is_some_and(|email| !email.is_empty())
+}
+
+pub fn setup_config(config: &mut JiraConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<JiraConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<JiraConfig>(CONFIG_KEY);
+}
@@ -0,0 +1,179 @@
+pub mod config;
+pub mod http_data;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use http_data::*;
+use log::{debug, error};
+use reqwest::{Client, RequestBuilder};
+use simplelog::info;
+
+use crate::handler::kimai::config::{
+    choose, create_config, setup_config, update_config, KimaiConfig, KimaiSide,
+};
+use crate::tracker::config::{get_timeular_config, Handler, Side};
+
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Debug, Default)]
+pub struct Kimai {
+    client: Client,
+    config: KimaiConfig,
+}
+
+#[async_trait]
+impl Handler for Kimai {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let kimai_side = match self.config.find_side(&side.side_num) {
+            Some(kimai_side) => kimai_side,
+            None => {
+                info!(
+                    "No Kimai project configured for side {}, run with --setup to choose one",
+                    side
+                );
+                return;
+            }
+        };
+
+        let timesheet = TimesheetRequest {
+            begin: duration.0.format(DATE_FORMAT).to_string(),
+            end: duration.1.format(DATE_FORMAT).to_string(),
+            project: kimai_side.project_id,
+            activity: kimai_side.activity_id,
+            description: side.label.clone(),
+        };
+
+        let response = authorize(
+            self.client.post(api_url(&self.config, "timesheets")),
+            &self.config,
+        )
+        .json(&timesheet)
+        .send()
+        .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                debug!("Response: {}", response.text().await.unwrap_or_default())
+            }
+            Ok(response) => error!(
+                "Kimai responded with {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ),
+            Err(e) => error!("Kimai error {}", e),
+        }
+    }
+}
+
+fn api_url(config: &KimaiConfig, path: &str) -> String {
+    format!("{}/api/{}", config.base_url.trim_end_matches('/'), path)
+}
+
+fn authorize(request_builder: RequestBuilder, config: &KimaiConfig) -> RequestBuilder {
+    if config.username.is_empty() {
+        return request_builder.bearer_auth(&config.api_token);
+    }
+
+    request_builder
+        .header("X-AUTH-USER", &config.username)
+        .header("X-AUTH-TOKEN", &config.api_token)
+}
+
+async fn fetch<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    config: &KimaiConfig,
+    path: &str,
+) -> Result<T, reqwest::Error> {
+    authorize(client.get(api_url(config, path)), config)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<T>()
+        .await
+}
+
+pub async fn create_handler(setup: bool) -> Kimai {
+    let mut config = create_config();
+    let client = Client::builder().build().unwrap();
+    setup_config(&mut config, setup);
+
+    // Unmapped sides are only asked for during setup, a skipped side is logged when it's tracked
+    if setup {
+        setup_sides(&client, &mut config).await;
+    }
+
+    Kimai { client, config }
+}
+
+/// Lets the user choose a project and one of its activities for every labeled side
+async fn setup_sides(client: &Client, config: &mut KimaiConfig) {
+    let sides: Vec<Side> = get_timeular_config()
+        .sides
+        .into_iter()
+        .filter(|s| !s.label.is_empty())
+        .collect();
+    if sides.is_empty() {
+        return;
+    }
+
+    let projects = match fetch::<Vec<Project>>(client, config, "projects").await {
+        Ok(projects) => projects,
+        Err(e) => {
+            error!("Could not fetch Kimai projects: {}", e);
+            return;
+        }
+    };
+
+    let mut changed = false;
+    for side in sides {
+        let current = config.find_side(&side.side_num).cloned();
+
+        let project_id = match choose(
+            "project_id",
+            "Kimai project",
+            projects.iter().map(|p| (p.id, p.to_string())).collect(),
+            &side,
+            current.as_ref().map(|s| s.project_id),
+        ) {
+            Some(project_id) => project_id,
+            None => continue,
+        };
+
+        let activities = match fetch::<Vec<Activity>>(
+            client,
+            config,
+            format!("activities?project={}", project_id).as_str(),
+        )
+        .await
+        {
+            Ok(activities) => activities,
+            Err(e) => {
+                error!("Could not fetch Kimai activities: {}", e);
+                break;
+            }
+        };
+
+        // The current activity is only offered again if it belongs to the chosen project
+        let activity_id = match choose(
+            "activity_id",
+            "Kimai activity",
+            activities.iter().map(|a| (a.id, a.to_string())).collect(),
+            &side,
+            current.as_ref().map(|s| s.activity_id),
+        ) {
+            Some(activity_id) => activity_id,
+            None => continue,
+        };
+
+        config.set_side(KimaiSide {
+            side_num: side.side_num,
+            project_id,
+            activity_id,
+        });
+        changed = true;
+    }
+
+    if changed {
+        update_config(config);
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+use crate::tracker::config::Side;
+
+const CONFIG_KEY: &str = "kimai";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct KimaiConfig {
+    pub base_url: String,
+    pub username: String,
+    pub api_token: String,
+    pub sides: Vec<KimaiSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KimaiSide {
+    pub side_num: u8,
+    pub project_id: u64,
+    pub activity_id: u64,
+}
+
+impl<'de> Config<'de> for KimaiConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["api_token"]
+    }
+}
+
+impl KimaiConfig {
+    pub fn find_side(&self, side_num: &u8) -> Option<&KimaiSide> {
+        self.sides.iter().find(|s| s.side_num.eq(side_num))
+    }
+
+    pub fn set_side(&mut self, side: KimaiSide) {
+        self.sides.retain(|s| s.side_num != side.side_num);
+        self.sides.push(side);
+        self.sides.sort_by_key(|s| s.side_num);
+    }
+}
+
+pub fn create_config() -> KimaiConfig {
+    crate::config::get_config::<KimaiConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("base_url", "Kimai url")
+            .required()
+            .validate(wizard::url),
+        Field::text(
+            "username",
+            "Kimai username, only needed for the legacy API password",
+        ),
+        Field::text("api_token", "Kimai API token")
+            .secret()
+            .required(),
+    ]
+}
+
+pub fn setup_config(config: &mut KimaiConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<KimaiConfig>(CONFIG_KEY, &fields())
+}
+
+/// Lets the user pick one of `items` for `side` and returns its id, see `wizard::choose`
+pub fn choose(
+    name: &'static str,
+    description: &'static str,
+    items: Vec<(u64, String)>,
+    side: &Side,
+    current: Option<u64>,
+) -> Option<u64> {
+    let choices = items
+        .into_iter()
+        .map(|(id, label)| (id as i64, label))
+        .collect();
+    let field = Field::choice(name, description, choices);
+
+    wizard::choose(CONFIG_KEY, &field, Some(side), current.map(|id| id as i64)).map(|id| id as u64)
+}
+
+pub fn update_config(config: &KimaiConfig) {
+    crate::config::update_config(CONFIG_KEY, config);
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<KimaiConfig>(CONFIG_KEY);
+}
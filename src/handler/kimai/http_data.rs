@@ -0,0 +1,47 @@
+use std::fmt;
+
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimesheetRequest {
+    pub begin: String,
+    pub end: String,
+    pub project: u64,
+    pub activity: u64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Project {
+    pub id: u64,
+    pub name: String,
+    #[serde(rename = "parentTitle", default)]
+    pub customer: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Activity {
+    pub id: u64,
+    pub name: String,
+    #[serde(rename = "parentTitle", default)]
+    pub project: Option<String>,
+}
+
+impl fmt::Display for Project {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.customer {
+            Some(customer) => write!(f, "{} ({})", self.name, customer),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.project {
+            Some(project) => write!(f, "{} ({})", self.name, project),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
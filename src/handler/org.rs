@@ -0,0 +1,246 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use log::{debug, error};
+
+use crate::config::expand_path;
+use crate::handler::org::config::{create_config, setup_config, OrgConfig};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+
+const TIMESTAMP_FORMAT: &str = "[%Y-%m-%d %a %H:%M]";
+
+#[derive(Debug, Default)]
+pub struct Org {
+    config: OrgConfig,
+    path: PathBuf,
+}
+
+impl Org {
+    fn get_headings(&self, side: &Side, start: &DateTime<Local>) -> Vec<String> {
+        let mut headings = vec![];
+        if self.config.date_tree {
+            headings.push(start.format("%Y").to_string());
+            headings.push(start.format("%Y-%m %B").to_string());
+            headings.push(start.format("%Y-%m-%d %A").to_string());
+        }
+        headings.push(side.label.clone());
+
+        headings
+    }
+
+    fn write_clock(&self, headings: &[String], clock: &str) -> io::Result<()> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // The file holds the user's own notes, so it's never left half written
+        let tmp_path = self.path.with_extension("org.tmp");
+        fs::write(&tmp_path, insert_clock(&content, headings, clock))?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+#[async_trait]
+impl Handler for Org {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let minutes = (duration.1 - duration.0).num_minutes();
+        let clock = format!(
+            "CLOCK: {}--{} => {:>2}:{:02}",
+            duration.0.format(TIMESTAMP_FORMAT),
+            duration.1.format(TIMESTAMP_FORMAT),
+            minutes / 60,
+            minutes % 60
+        );
+        let headings = self.get_headings(side, &duration.0);
+
+        if let Err(e) = self.write_clock(&headings, &clock) {
+            error!("Could not write to {}: {}", self.path.display(), e);
+            return;
+        }
+
+        debug!("{} added under {}", clock, headings.join(" / "));
+    }
+}
+
+/// Inserts the clock line under the heading path, creating the missing headings at the end
+/// of their parent subtree. The clock line goes into the heading's `:LOGBOOK:` drawer if it has one.
+fn insert_clock(content: &str, headings: &[String], clock: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let mut start = 0;
+    let mut end = lines.len();
+
+    for (i, heading) in headings.iter().enumerate() {
+        let level = i + 1;
+        let found = (start..end)
+            .find(|&idx| heading_title(&lines[idx], level).is_some_and(|t| t == heading));
+
+        let idx = match found {
+            Some(idx) => idx,
+            None => {
+                lines.insert(end, format!("{} {}", "*".repeat(level), heading));
+                end
+            }
+        };
+
+        start = idx + 1;
+        end = (start..lines.len())
+            .find(|&j| heading_level(&lines[j]).is_some_and(|l| l <= level))
+            .unwrap_or(lines.len());
+    }
+
+    let indent = " ".repeat(headings.len() + 1);
+    let mut idx = start;
+
+    let is_planning = |line: &str| {
+        let line = line.trim_start();
+        line.starts_with("SCHEDULED:")
+            || line.starts_with("DEADLINE:")
+            || line.starts_with("CLOSED:")
+    };
+    if idx < end && is_planning(&lines[idx]) {
+        idx += 1;
+    }
+    if idx < end && lines[idx].trim() == ":PROPERTIES:" {
+        while idx < end && lines[idx].trim() != ":END:" {
+            idx += 1;
+        }
+        idx = (idx + 1).min(end);
+    }
+    if idx < end && lines[idx].trim() == ":LOGBOOK:" {
+        idx += 1;
+    }
+
+    lines.insert(idx, format!("{}{}", indent, clock));
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+
+    content
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '*').count();
+    if level == 0 || !line[level..].starts_with(' ') {
+        return None;
+    }
+
+    Some(level)
+}
+
+fn heading_title(line: &str, level: usize) -> Option<&str> {
+    if heading_level(line)? != level {
+        return None;
+    }
+
+    let title = line[level..].trim();
+    // Strip trailing tags like `:work:meeting:`
+    match title.rsplit_once(char::is_whitespace) {
+        Some((rest, tags)) if tags.len() > 1 && tags.starts_with(':') && tags.ends_with(':') => {
+            Some(rest.trim_end())
+        }
+        _ => Some(title),
+    }
+}
+
+pub async fn create_handler(setup: bool) -> Org {
+    let mut config = create_config();
+    setup_config(&mut config, setup);
+    let path = expand_path(&config.path);
+
+    Org { config, path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLOCK: &str = "CLOCK: [2024-05-06 Mon 09:00]--[2024-05-06 Mon 10:00] =>  1:00";
+
+    fn headings(headings: &[&str]) -> Vec<String> {
+        headings.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn clock_goes_into_existing_logbook() {
+        let content = "* Work\n\
+                       ** Meetings :team:\n\
+                       SCHEDULED: <2024-05-06 Mon>\n\
+                       :PROPERTIES:\n\
+                       :ID: 1\n\
+                       :END:\n\
+                       :LOGBOOK:\n\
+                       CLOCK: old\n\
+                       :END:\n\
+                       Notes\n";
+
+        let result = insert_clock(content, &headings(&["Work", "Meetings"]), CLOCK);
+
+        assert_eq!(
+            result,
+            format!(
+                "* Work\n\
+                 ** Meetings :team:\n\
+                 SCHEDULED: <2024-05-06 Mon>\n\
+                 :PROPERTIES:\n\
+                 :ID: 1\n\
+                 :END:\n\
+                 :LOGBOOK:\n   {}\n\
+                 CLOCK: old\n\
+                 :END:\n\
+                 Notes\n",
+                CLOCK
+            )
+        );
+    }
+
+    #[test]
+    fn clock_goes_below_heading_without_logbook() {
+        let content = "* Meetings\nNotes\n* Email\n";
+
+        let result = insert_clock(content, &headings(&["Meetings"]), CLOCK);
+
+        assert_eq!(result, format!("* Meetings\n  {}\nNotes\n* Email\n", CLOCK));
+    }
+
+    #[test]
+    fn missing_headings_are_created_in_their_parent() {
+        let content = "* 2024\n** 2024-05 May\n*** 2024-05-05 Sunday\n* Inbox\n";
+
+        let result = insert_clock(
+            content,
+            &headings(&["2024", "2024-05 May", "2024-05-06 Monday", "Meetings"]),
+            CLOCK,
+        );
+
+        assert_eq!(
+            result,
+            format!(
+                "* 2024\n\
+                 ** 2024-05 May\n\
+                 *** 2024-05-05 Sunday\n\
+                 *** 2024-05-06 Monday\n\
+                 **** Meetings\n     {}\n\
+                 * Inbox\n",
+                CLOCK
+            )
+        );
+    }
+
+    #[test]
+    fn heading_is_created_in_empty_file() {
+        let result = insert_clock("", &headings(&["Meetings"]), CLOCK);
+
+        assert_eq!(result, format!("* Meetings\n  {}\n", CLOCK));
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "org";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OrgConfig {
+    pub path: String,
+    pub date_tree: bool,
+}
+
+impl Default for OrgConfig {
+    fn default() -> Self {
+        OrgConfig {
+            path: String::from("~/org/timeular.org"),
+            date_tree: false,
+        }
+    }
+}
+impl<'de> Config<'de> for OrgConfig {}
+
+pub fn create_config() -> OrgConfig {
+    crate::config::get_config::<OrgConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("path", "org file path"),
+        Field::flag("date_tree", "Put the headings under a date tree"),
+    ]
+}
+
+pub fn setup_config(config: &mut OrgConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<OrgConfig>(CONFIG_KEY, &fields())
+}
@@ -0,0 +1,87 @@
+pub mod config;
+pub mod http_data;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use http_data::*;
+use log::{debug, error};
+use reqwest::Client;
+use simplelog::info;
+
+use crate::handler::redmine::config::{create_config, setup_config, RedmineConfig};
+use crate::tracker::config::{Handler, Side};
+
+#[derive(Debug, Default)]
+pub struct Redmine {
+    client: Client,
+    config: RedmineConfig,
+}
+
+#[async_trait]
+impl Handler for Redmine {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let redmine_side = match self.config.find_side(&side.side_num) {
+            Some(redmine_side)
+                if redmine_side.issue_id != 0 || !redmine_side.project_id.is_empty() =>
+            {
+                redmine_side
+            }
+            _ => {
+                info!(
+                    "No Redmine issue or project configured for side {}, run with --setup to set one",
+                    side
+                );
+                return;
+            }
+        };
+
+        // Redmine rejects time entries with 0 hours
+        let hours = ((duration.1 - duration.0).num_seconds() as f64 / 36.0).round() / 100.0;
+        if hours == 0.0 {
+            info!("Redmine doesn't accept time entries shorter than 18 seconds, skipping");
+            return;
+        }
+
+        let time_entry = TimeEntryRequest {
+            time_entry: TimeEntryData {
+                issue_id: Some(redmine_side.issue_id).filter(|id| *id != 0),
+                project_id: Some(redmine_side.project_id.clone()).filter(|id| !id.is_empty()),
+                spent_on: duration.0.format("%Y-%m-%d").to_string(),
+                hours,
+                activity_id: Some(redmine_side.activity_id).filter(|id| *id != 0),
+                comments: side.label.clone(),
+            },
+        };
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/time_entries.json",
+                self.config.base_url.trim_end_matches('/')
+            ))
+            .header("X-Redmine-API-Key", &self.config.api_key)
+            .json(&time_entry)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                debug!("Response: {}", response.text().await.unwrap_or_default())
+            }
+            Ok(response) => error!(
+                "Redmine responded with {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ),
+            Err(e) => error!("Redmine error {}", e),
+        }
+    }
+}
+
+pub async fn create_handler(setup: bool) -> Redmine {
+    let mut config = create_config();
+    let client = Client::builder().build().unwrap();
+    setup_config(&mut config, setup);
+
+    Redmine { client, config }
+}
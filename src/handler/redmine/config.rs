@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use toml::Value;
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "redmine";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RedmineConfig {
+    pub base_url: String,
+    pub api_key: String,
+    pub sides: Vec<RedmineSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RedmineSide {
+    pub side_num: u8,
+    pub issue_id: u64,
+    pub project_id: String,
+    pub activity_id: u64,
+}
+
+impl<'de> Config<'de> for RedmineConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["api_key"]
+    }
+}
+
+impl RedmineConfig {
+    pub fn find_side(&self, side_num: &u8) -> Option<&RedmineSide> {
+        self.sides.iter().find(|s| s.side_num.eq(side_num))
+    }
+}
+
+pub fn create_config() -> RedmineConfig {
+    crate::config::get_config::<RedmineConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("base_url", "Redmine url")
+            .required()
+            .validate(wizard::url),
+        Field::text("api_key", "Redmine API key")
+            .secret()
+            .required(),
+        Field::number("issue_id", "Redmine issue id").per_side(),
+        Field::text("project_id", "Redmine project id or identifier")
+            .per_side()
+            .only_if(|side| {
+                side.get("issue_id")
+                    .and_then(Value::as_integer)
+                    .unwrap_or(0)
+                    == 0
+            }),
+        Field::number(
+            "activity_id",
+            "Redmine activity id, the default activity is used when it's not set",
+        )
+        .per_side(),
+    ]
+}
+
+pub fn setup_config(config: &mut RedmineConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<RedmineConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<RedmineConfig>(CONFIG_KEY);
+}
@@ -0,0 +1,20 @@
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntryRequest {
+    pub time_entry: TimeEntryData,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntryData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    pub spent_on: String,
+    pub hours: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_id: Option<u64>,
+    pub comments: String,
+}
@@ -0,0 +1,59 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use log::{debug, error};
+
+use crate::config::expand_path;
+use crate::handler::timeclock::config::{create_config, setup_config, TimeclockConfig};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+
+const DATE_FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+
+#[derive(Debug, Default)]
+pub struct Timeclock {
+    config: TimeclockConfig,
+    path: PathBuf,
+}
+
+#[async_trait]
+impl Handler for Timeclock {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let account = self
+            .config
+            .get_account(&side.side_num)
+            .unwrap_or(&side.label);
+        let entry = format!(
+            "i {} {}  {}\no {}\n",
+            duration.0.format(DATE_FORMAT),
+            account,
+            side.label,
+            duration.1.format(DATE_FORMAT)
+        );
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(entry.as_bytes()));
+
+        if let Err(e) = result {
+            error!("Could not write to {}: {}", self.path.display(), e);
+            return;
+        }
+
+        debug!("Timeclock entry written:\n{}", entry.trim_end());
+    }
+}
+
+pub async fn create_handler(setup: bool) -> Timeclock {
+    let mut config = create_config();
+    setup_config(&mut config, setup);
+    let path = expand_path(&config.path);
+
+    Timeclock { config, path }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "timeclock";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TimeclockConfig {
+    pub path: String,
+    pub sides: Vec<TimeclockSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimeclockSide {
+    pub side_num: u8,
+    pub account: String,
+}
+
+impl Default for TimeclockConfig {
+    fn default() -> Self {
+        TimeclockConfig {
+            path: String::from("timeular.timeclock"),
+            sides: vec![],
+        }
+    }
+}
+impl<'de> Config<'de> for TimeclockConfig {}
+
+impl TimeclockConfig {
+    pub fn get_account(&self, side_num: &u8) -> Option<&String> {
+        self.sides
+            .iter()
+            .find(|s| s.side_num.eq(side_num))
+            .map(|s| &s.account)
+            .filter(|account| !account.is_empty())
+    }
+}
+
+pub fn create_config() -> TimeclockConfig {
+    crate::config::get_config::<TimeclockConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![Field::text("path", "timeclock file path")]
+}
+
+pub fn setup_config(config: &mut TimeclockConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<TimeclockConfig>(CONFIG_KEY, &fields())
+}
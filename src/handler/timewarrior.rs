@@ -0,0 +1,151 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
+use log::{debug, error};
+use tokio::process::Command;
+
+use crate::config::expand_path;
+use crate::handler::timewarrior::config::{create_config, setup_config, TimewarriorConfig};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+
+const DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Default)]
+pub struct Timewarrior {
+    config: TimewarriorConfig,
+    data_dir: PathBuf,
+}
+
+impl Timewarrior {
+    fn get_tags(&self, side: &Side) -> Vec<String> {
+        let mut tags = vec![side.label.clone()];
+        tags.extend(self.config.get_tags(&side.side_num));
+
+        tags
+    }
+
+    fn write_interval(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let start = duration.0.with_timezone(&Utc);
+        let end = duration.1.with_timezone(&Utc);
+        let tags = self
+            .get_tags(side)
+            .iter()
+            .map(|tag| quote_tag(tag))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let line = format!(
+            "inc {} - {} # {}\n",
+            start.format(DATE_FORMAT),
+            end.format(DATE_FORMAT),
+            tags
+        );
+
+        let path = self
+            .data_dir
+            .join(format!("{}.data", start.format("%Y-%m")));
+
+        let result = fs::create_dir_all(&self.data_dir).and_then(|_| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(line.as_bytes()))
+        });
+
+        if let Err(e) = result {
+            error!("Could not write to {}: {}", path.display(), e);
+            return;
+        }
+
+        debug!("Interval written to {}: {}", path.display(), line.trim());
+    }
+
+    async fn timew(&self, args: Vec<String>) {
+        debug!("Running {} {:?}", self.config.timew_path, args);
+
+        let output = Command::new(&self.config.timew_path)
+            .args(&args)
+            .output()
+            .await;
+
+        match output {
+            Ok(output) if output.status.success() => {
+                debug!("{}", String::from_utf8_lossy(&output.stdout).trim());
+            }
+            Ok(output) => error!(
+                "{} exited with {}: {}",
+                self.config.timew_path,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Err(e) => error!("Could not run {}: {}", self.config.timew_path, e),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for Timewarrior {
+    async fn start(&self, side: &Side, _start: &DateTime<Local>) {
+        if !self.config.live {
+            return;
+        }
+
+        let mut args = vec![String::from("start")];
+        args.extend(self.get_tags(side));
+        self.timew(args).await;
+    }
+
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        if self.config.live {
+            self.timew(vec![String::from("stop")]).await;
+            return;
+        }
+
+        self.write_interval(side, duration);
+    }
+}
+
+fn quote_tag(tag: &str) -> String {
+    if tag.contains(char::is_whitespace) || tag.contains('"') {
+        return format!("\"{}\"", tag.replace('"', "\\\""));
+    }
+
+    tag.to_string()
+}
+
+fn get_data_dir(config: &TimewarriorConfig) -> PathBuf {
+    if !config.data_dir.is_empty() {
+        return expand_path(&config.data_dir);
+    }
+
+    if let Ok(db) = env::var("TIMEWARRIORDB") {
+        return Path::new(&db).join("data");
+    }
+
+    let legacy = expand_path("~/.timewarrior");
+    if legacy.exists() {
+        return legacy.join("data");
+    }
+
+    dirs::data_dir()
+        .map(|dir| dir.join("timewarrior").join("data"))
+        .unwrap_or_else(|| legacy.join("data"))
+}
+
+pub async fn create_handler(setup: bool) -> Timewarrior {
+    let mut config = create_config();
+    setup_config(&mut config, setup);
+    let data_dir = get_data_dir(&config);
+
+    if !config.live {
+        debug!("Timewarrior data directory: {}", data_dir.display());
+    }
+
+    Timewarrior { config, data_dir }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "timewarrior";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TimewarriorConfig {
+    pub data_dir: String,
+    pub live: bool,
+    pub timew_path: String,
+    pub sides: Vec<TimewarriorSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimewarriorSide {
+    pub side_num: u8,
+    pub tags: Vec<String>,
+}
+
+impl Default for TimewarriorConfig {
+    fn default() -> Self {
+        TimewarriorConfig {
+            data_dir: String::new(),
+            live: false,
+            timew_path: String::from("timew"),
+            sides: vec![],
+        }
+    }
+}
+impl<'de> Config<'de> for TimewarriorConfig {}
+
+impl TimewarriorConfig {
+    pub fn get_tags(&self, side_num: &u8) -> Vec<String> {
+        self.sides
+            .iter()
+            .find(|s| s.side_num.eq(side_num))
+            .map(|s| s.tags.clone())
+            .unwrap_or_default()
+    }
+}
+
+pub fn create_config() -> TimewarriorConfig {
+    crate::config::get_config::<TimewarriorConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![Field::flag(
+        "live",
+        "Use timew start/stop for live tracking instead of writing data files",
+    )]
+}
+
+pub fn setup_config(config: &mut TimewarriorConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<TimewarriorConfig>(CONFIG_KEY, &fields())
+}
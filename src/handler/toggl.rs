@@ -3,17 +3,12 @@ use chrono::{DateTime, Local, SecondsFormat};
 use log::debug;
 use reqwest::header::CONTENT_TYPE;
 use reqwest::Client;
-use rpassword::prompt_password;
-use simplelog::info;
 use tinytemplate::TinyTemplate;
 
 use crate::handler::toggl::config::Context;
-use crate::{
-    handler::toggl::config::update_config,
-    tracker::config::{Handler, Side},
-};
+use crate::tracker::config::{Handler, Side};
 
-use self::config::{create_config, TogglConfig};
+use self::config::{create_config, setup_config, TogglConfig};
 
 pub mod config;
 
@@ -48,7 +43,10 @@ impl Handler for Toggl {
             "workspace_id": {workspace_id},
             "description": "{label}"
         }}"#,
-            project_id = self.config.project_id,
+            project_id = self
+                .config
+                .get_project_id(&side.side_num)
+                .map_or(String::from("null"), |id| id.to_string()),
             start = duration.0.to_rfc3339_opts(SecondsFormat::Secs, true),
             stop = duration.1.to_rfc3339_opts(SecondsFormat::Secs, true),
             workspace_id = self.config.workspace_id,
@@ -94,95 +92,7 @@ impl Handler for Toggl {
 pub async fn create_handler(setup: bool) -> Toggl {
     let mut config = create_config();
     let client = Client::builder().build().unwrap();
-    update_vendor_config(&mut config, setup);
+    setup_config(&mut config, setup);
 
     return Toggl { client, config };
 }
-
-fn update_vendor_config(config: &mut TogglConfig, setup: bool) {
-    if setup || config.workspace_id == 0 {
-        let mut workspace_id = String::new();
-        let mut message =
-            String::from_utf8("Provide your Toggl workspace id".as_bytes().to_vec()).unwrap();
-        if config.workspace_id != 0 {
-            message.push_str(
-                format!(
-                    "\ncurrent value {}, leave blank to skip",
-                    config.workspace_id
-                )
-                .as_str(),
-            );
-        }
-        info!("{message}");
-
-        std::io::stdin()
-            .read_line(&mut workspace_id)
-            .expect("Please provide workspace_id");
-        workspace_id = workspace_id.trim().to_string();
-
-        if !workspace_id.is_empty() {
-            config.workspace_id = workspace_id.parse::<u64>().unwrap();
-            update_config(&config);
-        }
-    }
-
-    if setup || config.project_id == 0 {
-        let mut project_id = String::new();
-        let mut message =
-            String::from_utf8("Provide your Toggl project id".as_bytes().to_vec()).unwrap();
-        if config.project_id != 0 {
-            message.push_str(
-                format!("\ncurrent value {}, leave blank to skip", config.project_id).as_str(),
-            );
-        }
-        log::info!("{message}");
-
-        std::io::stdin()
-            .read_line(&mut project_id)
-            .expect("Please provide project_id");
-
-        project_id = project_id.trim().to_string();
-
-        if !project_id.is_empty() {
-            config.project_id = project_id.parse::<u64>().unwrap();
-            update_config(&config);
-        }
-    }
-
-    if setup || config.email.is_empty() {
-        let mut email = String::new();
-        let mut message =
-            String::from_utf8("Provide your Toggl email".as_bytes().to_vec()).unwrap();
-        if !config.email.is_empty() {
-            message.push_str(
-                format!("\ncurrent value {}, leave blank to skip", config.email).as_str(),
-            );
-        }
-        log::info!("{message}");
-
-        std::io::stdin()
-            .read_line(&mut email)
-            .expect("Please provide email");
-
-        email = email.trim().to_string();
-
-        if !email.is_empty() {
-            config.email = email;
-            update_config(&config);
-        }
-    }
-
-    if setup || config.password.is_empty() {
-        let mut message =
-            String::from_utf8("Provide your Toggl password".as_bytes().to_vec()).unwrap();
-        if !config.password.is_empty() {
-            message.push_str("\nleave blank to use current value");
-        }
-        let password: String = (*prompt_password(message).unwrap().trim()).to_string();
-
-        if !password.is_empty() {
-            config.password = password;
-            update_config(&config);
-        }
-    }
-}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::wizard::{self, Field};
 use crate::config::Config;
 
 const CONFIG_KEY: &str = "toggl";
@@ -9,13 +10,23 @@ pub struct Context {
     pub workspace_id: u64,
 }
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct TogglConfig {
     pub base_url: String,
     pub time_entries_uri: String,
     pub email: String,
     pub password: String,
-    pub project_id: u64,
     pub workspace_id: u64,
+    /// Used for sides without a `project_id`, kept from configs older than per side projects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<u64>,
+    pub sides: Vec<TogglSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TogglSide {
+    pub side_num: u8,
+    pub project_id: u64,
 }
 
 impl Default for TogglConfig {
@@ -25,17 +36,53 @@ impl Default for TogglConfig {
             time_entries_uri: String::from("api/v9/workspaces/{workspace_id}/time_entries"),
             email: String::new(),
             password: String::new(),
-            project_id: 0,
             workspace_id: 0,
+            project_id: None,
+            sides: vec![],
         }
     }
 }
-impl<'de> Config<'de> for TogglConfig {}
+impl<'de> Config<'de> for TogglConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["password"]
+    }
+}
+
+impl TogglConfig {
+    pub fn get_project_id(&self, side_num: &u8) -> Option<u64> {
+        self.sides
+            .iter()
+            .find(|s| s.side_num.eq(side_num))
+            .map(|s| s.project_id)
+            .or(self.project_id)
+    }
+}
 
 pub fn create_config() -> TogglConfig {
     crate::config::get_config::<TogglConfig>(CONFIG_KEY)
 }
 
-pub fn update_config(config: &TogglConfig) {
-    crate::config::update_config(CONFIG_KEY, config);
+fn fields() -> Vec<Field> {
+    vec![
+        Field::number("workspace_id", "Toggl workspace id").required(),
+        Field::number("project_id", "Toggl project id").per_side(),
+        Field::text("email", "Toggl email")
+            .required()
+            .validate(wizard::email),
+        Field::text("password", "Toggl password")
+            .secret()
+            .required(),
+    ]
+}
+
+pub fn setup_config(config: &mut TogglConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<TogglConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<TogglConfig>(CONFIG_KEY);
 }
@@ -0,0 +1,163 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use log::{debug, error};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::config::expand_path;
+use crate::handler::watson::config::{create_config, setup_config, WatsonConfig};
+use crate::tracker::config::{Handler, Side};
+
+pub mod config;
+
+const ATTEMPTS: u8 = 5;
+
+#[derive(Debug, Default)]
+pub struct Watson {
+    config: WatsonConfig,
+    frames_path: PathBuf,
+}
+
+impl Watson {
+    fn create_frame(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) -> Value {
+        let (project, tags) = match self.config.find_side(&side.side_num) {
+            Some(watson_side) if !watson_side.project.is_empty() => {
+                (watson_side.project.clone(), watson_side.tags.clone())
+            }
+            Some(watson_side) => (side.label.clone(), watson_side.tags.clone()),
+            None => (side.label.clone(), vec![]),
+        };
+
+        json!([
+            duration.0.timestamp(),
+            duration.1.timestamp(),
+            project,
+            Uuid::new_v4().simple().to_string(),
+            tags,
+            Local::now().timestamp()
+        ])
+    }
+}
+
+/// Watson never locks the frames file, so instead the file is checked right before the new
+/// content is renamed into place and the update is retried if it changed in the meantime
+fn append_frame(frames_path: &Path, frame: Value) -> io::Result<()> {
+    if let Some(parent) = frames_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = frames_path.with_extension("tmp");
+
+    for _ in 0..ATTEMPTS {
+        let before = file_state(frames_path)?;
+        let mut frames: Vec<Value> = match fs::read_to_string(frames_path) {
+            Ok(content) if content.trim().is_empty() => vec![],
+            Ok(content) => serde_json::from_str(&content).map_err(io::Error::other)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e),
+        };
+        frames.push(frame.clone());
+
+        fs::write(
+            &tmp_path,
+            serde_json::to_string_pretty(&frames).map_err(io::Error::other)?,
+        )?;
+
+        if file_state(frames_path)? == before {
+            return fs::rename(&tmp_path, frames_path);
+        }
+        debug!("{} changed while writing, retrying", frames_path.display());
+    }
+
+    let _ = fs::remove_file(&tmp_path);
+    Err(io::Error::other(format!(
+        "the file kept changing, gave up after {} attempts",
+        ATTEMPTS
+    )))
+}
+
+/// Modification time and length, `None` if the file doesn't exist
+fn file_state(path: &Path) -> io::Result<Option<(SystemTime, u64)>> {
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(Some((metadata.modified()?, metadata.len()))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[async_trait]
+impl Handler for Watson {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let frame = self.create_frame(side, duration);
+        debug!("Watson frame: {}", frame);
+
+        let frames_path = self.frames_path.clone();
+        let result = tokio::task::spawn_blocking(move || append_frame(&frames_path, frame))
+            .await
+            .unwrap_or_else(|e| Err(io::Error::other(e)));
+
+        if let Err(e) = result {
+            error!(
+                "Could not write frame to {}: {}",
+                self.frames_path.display(),
+                e
+            );
+        }
+    }
+}
+
+fn get_frames_path(config: &WatsonConfig) -> PathBuf {
+    if !config.frames_path.is_empty() {
+        return expand_path(&config.frames_path);
+    }
+
+    if let Ok(dir) = env::var("WATSON_DIR") {
+        return Path::new(&dir).join("frames");
+    }
+
+    dirs::config_dir()
+        .unwrap_or_else(|| expand_path("~/.config"))
+        .join("watson")
+        .join("frames")
+}
+
+pub async fn create_handler(setup: bool) -> Watson {
+    let mut config = create_config();
+    setup_config(&mut config, setup);
+    let frames_path = get_frames_path(&config);
+    debug!("Watson frames file: {}", frames_path.display());
+
+    Watson {
+        config,
+        frames_path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_appended() {
+        let dir = env::temp_dir().join(format!("timeular-watson-{}", Uuid::new_v4().simple()));
+        let frames_path = dir.join("frames");
+
+        append_frame(&frames_path, json!([1, 2, "a", "id-1", [], 3])).unwrap();
+        append_frame(&frames_path, json!([4, 5, "b", "id-2", ["x"], 6])).unwrap();
+
+        let frames: Value =
+            serde_json::from_str(&fs::read_to_string(&frames_path).unwrap()).unwrap();
+        assert_eq!(
+            frames,
+            json!([[1, 2, "a", "id-1", [], 3], [4, 5, "b", "id-2", ["x"], 6]])
+        );
+        assert!(!frames_path.with_extension("tmp").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
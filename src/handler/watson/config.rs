@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "watson";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct WatsonConfig {
+    pub frames_path: String,
+    pub sides: Vec<WatsonSide>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WatsonSide {
+    pub side_num: u8,
+    pub project: String,
+    pub tags: Vec<String>,
+}
+
+impl<'de> Config<'de> for WatsonConfig {}
+
+impl WatsonConfig {
+    pub fn find_side(&self, side_num: &u8) -> Option<&WatsonSide> {
+        self.sides.iter().find(|s| s.side_num.eq(side_num))
+    }
+}
+
+pub fn create_config() -> WatsonConfig {
+    crate::config::get_config::<WatsonConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![Field::text(
+        "frames_path",
+        "Watson frames file path, the Watson default is used when it's not set",
+    )]
+}
+
+pub fn setup_config(config: &mut WatsonConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<WatsonConfig>(CONFIG_KEY, &fields())
+}
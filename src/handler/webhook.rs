@@ -0,0 +1,138 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, SecondsFormat};
+use log::{debug, error};
+use reqwest::{Client, Method};
+use serde_json::Value;
+use tinytemplate::{format_unescaped, TinyTemplate};
+
+use crate::handler::webhook::config::{Context, WebhookAuth};
+use crate::tracker::config::{Handler, Side};
+
+use self::config::{create_config, setup_config, WebhookConfig};
+
+pub mod config;
+
+#[derive(Debug, Default)]
+pub struct Webhook {
+    client: Client,
+    config: WebhookConfig,
+}
+
+impl Webhook {
+    fn render(
+        &self,
+        template: &str,
+        context: &Context,
+    ) -> Result<String, tinytemplate::error::Error> {
+        let mut tt = TinyTemplate::new();
+        tt.set_default_formatter(&format_unescaped);
+        tt.add_formatter("json", format_json);
+        tt.add_formatter("url", format_url);
+        tt.add_template("template", template)?;
+
+        tt.render("template", context)
+    }
+
+    fn build_request(&self, context: &Context) -> Result<reqwest::RequestBuilder, String> {
+        let method = self
+            .render(&self.config.method, context)
+            .map_err(|e| e.to_string())?;
+        let method = Method::from_bytes(method.trim().to_uppercase().as_bytes())
+            .map_err(|e| e.to_string())?;
+        let url = self
+            .render(&self.config.url, context)
+            .map_err(|e| e.to_string())?;
+        let body = self
+            .render(&self.config.body, context)
+            .map_err(|e| e.to_string())?;
+
+        let mut request_builder = self.client.request(method, url.trim()).body(body);
+
+        for (name, value) in self.config.headers.iter() {
+            let value = self.render(value, context).map_err(|e| e.to_string())?;
+            request_builder = request_builder.header(name, value);
+        }
+
+        request_builder = match self.config.auth {
+            WebhookAuth::None => request_builder,
+            WebhookAuth::Bearer => request_builder.bearer_auth(&self.config.token),
+            WebhookAuth::Basic => {
+                request_builder.basic_auth(&self.config.username, Some(&self.config.password))
+            }
+            WebhookAuth::Header => {
+                request_builder.header(&self.config.auth_header, &self.config.token)
+            }
+        };
+
+        Ok(request_builder)
+    }
+}
+
+#[async_trait]
+impl Handler for Webhook {
+    async fn handle(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let context = Context {
+            side_num: side.side_num,
+            label: side.label.clone(),
+            start: duration.0.to_rfc3339_opts(SecondsFormat::Secs, true),
+            end: duration.1.to_rfc3339_opts(SecondsFormat::Secs, true),
+            duration: (duration.1 - duration.0).num_seconds(),
+        };
+
+        let request_builder = match self.build_request(&context) {
+            Ok(request_builder) => request_builder,
+            Err(e) => {
+                error!("Could not build webhook request: {}", e);
+                return;
+            }
+        };
+
+        let response = match request_builder.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Webhook error {}", e);
+                return;
+            }
+        };
+
+        if !response.status().is_success() {
+            error!("Webhook responded with {}", response.status());
+        }
+
+        debug!("Response: {}", response.text().await.unwrap_or_default());
+    }
+}
+
+fn format_json(value: &Value, output: &mut String) -> tinytemplate::error::Result<()> {
+    output.push_str(&value.to_string());
+
+    Ok(())
+}
+
+fn format_url(value: &Value, output: &mut String) -> tinytemplate::error::Result<()> {
+    let value = match value {
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    };
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                output.push(byte as char)
+            }
+            _ => write!(output, "%{:02X}", byte).unwrap(),
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn create_handler(setup: bool) -> Webhook {
+    let mut config = create_config();
+    let client = Client::builder().build().unwrap();
+    setup_config(&mut config, setup);
+
+    Webhook { client, config }
+}
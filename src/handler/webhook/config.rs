@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use toml::{value::Table, Value};
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+const CONFIG_KEY: &str = "webhook";
+
+#[derive(Serialize)]
+pub struct Context {
+    pub side_num: u8,
+    pub label: String,
+    pub start: String,
+    pub end: String,
+    pub duration: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookAuth {
+    None,
+    Bearer,
+    Basic,
+    Header,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub method: String,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    pub auth: WebhookAuth,
+    pub auth_header: String,
+    pub token: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig {
+            method: String::from("POST"),
+            url: String::new(),
+            headers: HashMap::from([(
+                String::from("Content-Type"),
+                String::from("application/json"),
+            )]),
+            body: String::from(
+                r#"\{"side_num": {side_num}, "label": {label | json}, "start": "{start}", "end": "{end}", "duration": {duration}}"#,
+            ),
+            auth: WebhookAuth::None,
+            auth_header: String::from("x-api-key"),
+            token: String::new(),
+            username: String::new(),
+            password: String::new(),
+        }
+    }
+}
+impl<'de> Config<'de> for WebhookConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["token", "password"]
+    }
+}
+
+pub fn create_config() -> WebhookConfig {
+    crate::config::get_config::<WebhookConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("url", "webhook url")
+            .required()
+            .validate(wizard::url),
+        Field::text("username", "webhook username")
+            .required()
+            .only_if(is_basic_auth),
+        Field::text("password", "webhook password")
+            .secret()
+            .required()
+            .only_if(is_basic_auth),
+        Field::text("token", "webhook token")
+            .secret()
+            .required()
+            .only_if(|config| {
+                matches!(
+                    config.get("auth").and_then(Value::as_str),
+                    Some("bearer" | "header")
+                )
+            }),
+    ]
+}
+
+fn is_basic_auth(config: &Table) -> bool {
+    config.get("auth").and_then(Value::as_str) == Some("basic")
+}
+
+pub fn setup_config(config: &mut WebhookConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<WebhookConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<WebhookConfig>(CONFIG_KEY);
+}
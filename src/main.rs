@@ -1,16 +1,18 @@
 extern crate core;
 
 use std::error::Error;
+use std::path::PathBuf;
+use std::process;
 use std::sync::Arc;
 
 use btleplug::api::{Central, CentralEvent, Manager as _, Peripheral, ScanFilter};
 use btleplug::platform::{Adapter, Manager, PeripheralId};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use futures::stream::StreamExt;
-use log::{debug, LevelFilter};
+use log::{debug, error, LevelFilter};
 use simplelog::{info, ColorChoice, ConfigBuilder, TermLogger, TerminalMode};
 
-use crate::tracker::reader;
+use crate::tracker::{check, reader};
 
 pub mod config;
 pub mod handler;
@@ -19,12 +21,78 @@ pub mod tracker;
 #[derive(Parser, Debug)]
 #[clap(about, long_about = None)]
 struct CliArgs {
-    #[clap(short, long, action)]
+    #[clap(short, long, action, conflicts_with = "no_prompt")]
     setup: bool,
+    /// Never ask for input, missing config values are errors, for running as a service
+    #[clap(long, action)]
+    no_prompt: bool,
     #[clap(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
     #[clap(short, long, action)]
     quiet: bool,
+    /// Path to the config file, defaults to $TIMEULAR_READER_CONFIG or the user config dir
+    #[clap(short, long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Profile to use instead of the active_profile set in the config
+    #[clap(short, long, value_name = "NAME")]
+    profile: Option<String>,
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage the config file
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Manage secrets stored in the system keyring
+    Secrets {
+        #[clap(subcommand)]
+        command: SecretsCommand,
+    },
+    /// Label the sides of the tracker without flipping it
+    Sides {
+        #[clap(subcommand)]
+        command: SidesCommand,
+    },
+    /// Switch between config profiles
+    Profile {
+        #[clap(subcommand)]
+        command: ProfileCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Check the config file for mistakes
+    Check,
+}
+
+#[derive(Subcommand, Debug)]
+enum SecretsCommand {
+    /// Move plaintext passwords and tokens from the config file to the system keyring
+    Migrate,
+}
+
+#[derive(Subcommand, Debug)]
+enum SidesCommand {
+    /// Set the label of a side, an empty label stops tracking it
+    Set { side_num: u8, label: String },
+    /// List the sides and their labels
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfileCommand {
+    /// Make a profile the active one, running readers switch to it when they reload the config
+    Use {
+        /// Name of the profile, "default" uses the sections outside of [profiles]
+        name: String,
+    },
+    /// List the profiles in the config file
+    List,
 }
 
 #[tokio::main]
@@ -32,6 +100,28 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let cli_args = CliArgs::parse();
 
     create_logger(&cli_args.verbose, cli_args.quiet);
+    config::init_config_path(cli_args.config);
+    if let Err(e) = config::profile::init_profile(cli_args.profile) {
+        error!("{}", e);
+        process::exit(1);
+    }
+    config::prompt::init_no_prompt(cli_args.no_prompt);
+    config::migration::migrate();
+    debug!("config path: \"{}\"", config::get_config_path().display());
+
+    if let Some(command) = cli_args.command {
+        run_command(command);
+        return Ok(());
+    }
+
+    if !check::report() {
+        process::exit(1);
+    }
+
+    if cli_args.no_prompt && tracker::config::get_timeular_config().handler.is_empty() {
+        error!("No handler is set, add one to [timeular] in the config file");
+        process::exit(1);
+    }
 
     debug!("{}", cli_args.setup);
     let adapter = Arc::new(get_adapter().await);
@@ -81,6 +171,50 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn run_command(command: Command) {
+    match command {
+        Command::Config {
+            command: ConfigCommand::Check,
+        } => {
+            if !check::report() {
+                process::exit(1);
+            }
+            info!("Config is valid");
+        }
+        Command::Secrets {
+            command: SecretsCommand::Migrate,
+        } => {
+            handler::migrate_secrets();
+            tracker::mqtt::config::migrate_secrets();
+            info!("Secrets migration finished");
+        }
+        Command::Sides {
+            command: SidesCommand::Set { side_num, label },
+        } => match tracker::config::label_side(side_num, label) {
+            Ok(()) => info!("Side {} saved", side_num),
+            Err(e) => {
+                error!("{}", e);
+                process::exit(1);
+            }
+        },
+        Command::Sides {
+            command: SidesCommand::List,
+        } => tracker::config::print_sides(),
+        Command::Profile {
+            command: ProfileCommand::Use { name },
+        } => match config::profile::use_profile(&name) {
+            Ok(()) => info!("Switched to profile {}", name),
+            Err(e) => {
+                error!("{}", e);
+                process::exit(1);
+            }
+        },
+        Command::Profile {
+            command: ProfileCommand::List,
+        } => config::profile::print_profiles(),
+    }
+}
+
 fn spawn_reader(id: PeripheralId, adapter: &Arc<Adapter>, setup: bool) {
     info!("Connecting to tracker...");
 
@@ -1,2 +1,5 @@
+pub mod check;
 pub mod config;
+pub mod hooks;
+pub mod mqtt;
 pub mod reader;
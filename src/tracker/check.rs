@@ -0,0 +1,333 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::ops::Range;
+
+use log::{error, warn};
+use strum::IntoEnumIterator;
+use toml_edit::{ImDocument, Item, TableLike};
+
+use crate::config::diagnostic::{check_section, suggest, Diagnostic, Severity};
+use crate::config::get_config_path;
+use crate::config::migration::VERSION_KEY;
+use crate::config::profile::{ACTIVE_PROFILE_KEY, DEFAULT_PROFILE, PROFILES_KEY};
+use crate::handler::{self, Handlers};
+use crate::tracker::config::{TimeularConfig, CONFIG_KEY, SIDES};
+use crate::tracker::mqtt::{self, config::MqttConfig};
+
+/// Checks the config file and logs every problem found, returns false if any of them is an error
+pub fn report() -> bool {
+    let path = get_config_path();
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    let diagnostics = check(&contents);
+
+    for diagnostic in &diagnostics {
+        match diagnostic.severity {
+            Severity::Error => error!("{}", diagnostic.render(path, &contents)),
+            Severity::Warning => warn!("{}", diagnostic.render(path, &contents)),
+        }
+    }
+
+    !diagnostics.iter().any(Diagnostic::is_error)
+}
+
+pub fn check(contents: &str) -> Vec<Diagnostic> {
+    let document = match ImDocument::parse(contents) {
+        Ok(document) => document,
+        Err(e) => return vec![Diagnostic::error(e.message(), e.span())],
+    };
+
+    let handler_names: Vec<String> = Handlers::iter()
+        .map(|h| format!("{:?}", h).to_lowercase())
+        .collect();
+    let mut diagnostics = vec![];
+
+    for (key, item) in document.iter() {
+        if key == VERSION_KEY {
+            if item.as_integer().is_none() {
+                diagnostics.push(Diagnostic::error(
+                    format!("{} has to be a number", VERSION_KEY),
+                    item.span(),
+                ));
+            }
+        } else if key == ACTIVE_PROFILE_KEY {
+            check_active_profile(&document, item, &mut diagnostics);
+        } else if key == PROFILES_KEY {
+            let profiles = match item.as_table_like() {
+                Some(profiles) => profiles,
+                None => {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{} has to be a table", PROFILES_KEY),
+                        item.span(),
+                    ));
+                    continue;
+                }
+            };
+
+            for (profile, item) in profiles.iter() {
+                let sections = match item.as_table_like() {
+                    Some(sections) => sections,
+                    None => {
+                        diagnostics.push(Diagnostic::error(
+                            format!("profile \"{}\" has to be a table", profile),
+                            item.span(),
+                        ));
+                        continue;
+                    }
+                };
+
+                for (key, item) in sections.iter() {
+                    let span = sections.key(key).and_then(|k| k.span());
+                    let path = [PROFILES_KEY, profile, key];
+                    check_key(
+                        &path,
+                        item,
+                        span,
+                        contents,
+                        &handler_names,
+                        &mut diagnostics,
+                    );
+                }
+            }
+        } else {
+            let span = document.key(key).and_then(|k| k.span());
+            check_key(
+                &[key],
+                item,
+                span,
+                contents,
+                &handler_names,
+                &mut diagnostics,
+            );
+        }
+    }
+
+    diagnostics
+}
+
+/// Checks a `[timeular]`, `[mqtt]` or handler section, at the top level or in a profile
+fn check_key(
+    path: &[&str],
+    item: &Item,
+    key_span: Option<Range<usize>>,
+    contents: &str,
+    handler_names: &[String],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let key = *path.last().unwrap();
+
+    if key == CONFIG_KEY {
+        diagnostics.extend(check_section::<TimeularConfig>(contents, path));
+        check_timeular(item, handler_names, diagnostics);
+    } else if key == mqtt::config::CONFIG_KEY {
+        diagnostics.extend(check_section::<MqttConfig>(contents, path));
+    } else if handler_names.iter().any(|name| name == key) {
+        diagnostics.extend(handler::check_config(key, path, item, contents));
+    } else {
+        let known = handler_names
+            .iter()
+            .map(String::as_str)
+            .chain([CONFIG_KEY, mqtt::config::CONFIG_KEY]);
+        let mut message = format!("unknown section [{}]", path.join("."));
+        if let Some(suggestion) = suggest(key, known) {
+            message.push_str(format!(", did you mean [{}]?", suggestion).as_str());
+        }
+        diagnostics.push(Diagnostic::warning(message, key_span));
+    }
+}
+
+fn check_active_profile(
+    document: &ImDocument<&str>,
+    item: &Item,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let name = match item.as_str() {
+        Some(name) => name,
+        None => {
+            diagnostics.push(Diagnostic::error(
+                format!("{} has to be a string", ACTIVE_PROFILE_KEY),
+                item.span(),
+            ));
+            return;
+        }
+    };
+
+    let profiles: Vec<&str> = document
+        .get(PROFILES_KEY)
+        .and_then(Item::as_table_like)
+        .map(|profiles| profiles.iter().map(|(name, _)| name).collect())
+        .unwrap_or_default();
+
+    if name == DEFAULT_PROFILE || profiles.contains(&name) {
+        return;
+    }
+
+    let mut message = format!("unknown profile \"{}\"", name);
+    if let Some(suggestion) = suggest(name, profiles.into_iter().chain([DEFAULT_PROFILE])) {
+        message.push_str(format!(", did you mean \"{}\"?", suggestion).as_str());
+    }
+    diagnostics.push(Diagnostic::error(message, item.span()));
+}
+
+fn check_timeular(item: &Item, handler_names: &[String], diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(handler) = item.get("handler") {
+        match handler.as_str() {
+            Some("") => diagnostics.push(Diagnostic::warning(
+                "no handler is set, run with --setup to choose one",
+                handler.span(),
+            )),
+            Some(name) if !handler_names.iter().any(|h| h == name) => {
+                let mut message = format!("unknown handler \"{}\"", name);
+                if let Some(suggestion) = suggest(name, handler_names.iter().map(String::as_str)) {
+                    message.push_str(format!(", did you mean \"{}\"?", suggestion).as_str());
+                }
+                diagnostics.push(Diagnostic::error(message, handler.span()));
+            }
+            _ => {}
+        }
+    }
+
+    let sides_item = match item.get("sides") {
+        Some(sides) => sides,
+        None => return,
+    };
+
+    let sides: Vec<&dyn TableLike> = match (sides_item.as_array_of_tables(), sides_item.as_array())
+    {
+        (Some(tables), _) => tables.iter().map(|t| t as &dyn TableLike).collect(),
+        (_, Some(array)) => array
+            .iter()
+            .filter_map(|v| v.as_inline_table())
+            .map(|t| t as &dyn TableLike)
+            .collect(),
+        _ => return,
+    };
+
+    let mut seen = BTreeSet::new();
+    for side in sides {
+        let side_num = match side.get("side_num") {
+            Some(side_num) => side_num,
+            None => continue,
+        };
+        let num = match side_num.as_integer() {
+            Some(num) => num,
+            None => continue,
+        };
+
+        if !u8::try_from(num).is_ok_and(|num| SIDES.contains(&num)) {
+            diagnostics.push(Diagnostic::error(
+                format!(
+                    "side_num {} is out of range, the tracker has sides {} to {}",
+                    num,
+                    SIDES.start(),
+                    SIDES.end()
+                ),
+                side_num.span(),
+            ));
+        } else if !seen.insert(num) {
+            diagnostics.push(Diagnostic::error(
+                format!("side {} is defined more than once", num),
+                side_num.span(),
+            ));
+        }
+    }
+
+    for num in SIDES.filter(|num| !seen.contains(&i64::from(*num))) {
+        diagnostics.push(Diagnostic::warning(
+            format!(
+                "side {} is missing, it is not tracked until it gets a label",
+                num
+            ),
+            sides_item.span().or_else(|| {
+                item.as_table_like()
+                    .and_then(|table| table.key("sides"))
+                    .and_then(|key| key.span())
+            }),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sides(nums: impl Iterator<Item = u8>) -> String {
+        nums.map(|num| format!("[[timeular.sides]]\nside_num = {}\n", num))
+            .collect()
+    }
+
+    #[test]
+    fn complete_config_is_valid() {
+        let contents = format!("[timeular]\nhandler = \"csv\"\n{}", sides(SIDES));
+
+        assert!(check(&contents).is_empty());
+    }
+
+    #[test]
+    fn missing_side_is_a_warning() {
+        let contents = format!("[timeular]\nhandler = \"csv\"\n{}", sides(0..=8));
+
+        let diagnostics = check(&contents);
+
+        assert_eq!(diagnostics.len(), 5);
+        assert!(diagnostics.iter().all(|d| !d.is_error()));
+        assert!(diagnostics[0].message.starts_with("side 9 is missing"));
+    }
+
+    #[test]
+    fn duplicate_and_out_of_range_sides_are_errors() {
+        let contents = format!(
+            "[timeular]\nhandler = \"csv\"\n{}{}",
+            sides(SIDES),
+            sides([3, 14].into_iter())
+        );
+
+        let errors: Vec<String> = check(&contents)
+            .into_iter()
+            .filter(Diagnostic::is_error)
+            .map(|d| d.message)
+            .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                String::from("side 3 is defined more than once"),
+                String::from("side_num 14 is out of range, the tracker has sides 0 to 13")
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_handler_and_section_get_suggestions() {
+        let contents = format!(
+            "[timeular]\nhandler = \"togl\"\n{}\n[clokify]\n",
+            sides(SIDES)
+        );
+
+        let messages: Vec<String> = check(&contents).into_iter().map(|d| d.message).collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                String::from("unknown handler \"togl\", did you mean \"toggl\"?"),
+                String::from("unknown section [clokify], did you mean [clockify]?")
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_heartbeat_interval_is_an_error() {
+        let contents = format!(
+            "[timeular]\nhandler = \"activitywatch\"\n{}\n[activitywatch]\nheartbeat_interval = 0\n",
+            sides(SIDES)
+        );
+
+        let diagnostics = check(&contents);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_error());
+        assert_eq!(
+            diagnostics[0].message,
+            "[activitywatch] heartbeat_interval has to be at least 1 second"
+        );
+    }
+}
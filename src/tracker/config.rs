@@ -3,28 +3,52 @@ use chrono::{DateTime, Local};
 use derive_more::Display;
 use log::debug;
 use serde::{Deserialize, Serialize};
+use simplelog::info;
+use std::ops::RangeInclusive;
 
 use crate::config::Config;
+use crate::tracker::check;
 
 pub const ORIENTATION_CHARACTERISTIC_UUID: &str = "c7e70012-c847-11e6-8175-8c89a55d403c";
-const CONFIG_KEY: &str = "timeular";
+pub const CONFIG_KEY: &str = "timeular";
+/// Side numbers the tracker reports
+pub const SIDES: RangeInclusive<u8> = 0..=13;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TimeularConfig {
     pub sides: Vec<Side>,
     pub handler: String,
+    pub hooks: Hooks,
 }
 
-#[derive(Debug, Serialize, Deserialize, Display, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Display, PartialEq, Clone, Default)]
 #[display(fmt = "{} {}", side_num, label)]
+#[serde(default)]
 pub struct Side {
     pub side_num: u8,
     pub label: String,
     pub configurable: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub on_enter: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub on_exit: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Hooks {
+    pub on_enter: String,
+    pub on_exit: String,
+    pub timeout: u64,
 }
 
 #[async_trait]
 pub trait Handler: Sync + Send {
+    async fn start(&self, side: &Side, start: &DateTime<Local>) {
+        debug!("handler start\n side: {:?}\n start {:?}", side, start)
+    }
+
     async fn handle(self: &Self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
         debug!("handler\n side: {:?}\n duration {:?}", side, duration)
     }
@@ -49,73 +73,98 @@ impl Default for TimeularConfig {
                     side_num: 1,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 2,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 3,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 4,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 5,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 6,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 7,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 8,
                     label: String::new(),
                     configurable: true,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 9,
                     label: String::new(),
                     configurable: false,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 10,
                     label: String::new(),
                     configurable: false,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 11,
                     label: String::new(),
                     configurable: false,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 12,
                     label: String::new(),
                     configurable: false,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 13,
                     label: String::new(),
                     configurable: false,
+                    ..Default::default()
                 },
                 Side {
                     side_num: 0,
                     label: String::new(),
                     configurable: false,
+                    ..Default::default()
                 },
             ],
+            hooks: Hooks::default(),
+        }
+    }
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Hooks {
+            on_enter: String::new(),
+            on_exit: String::new(),
+            timeout: 30,
         }
     }
 }
@@ -147,15 +196,81 @@ impl TimeularConfig {
                 side_num,
                 label,
                 configurable: true,
+                ..Default::default()
             });
         }
     }
+
+    /// Older configs can have fewer sides, the missing ones are taken from the default config
+    fn add_missing_sides(mut self) -> Self {
+        for side in TimeularConfig::default().sides {
+            if self.find_side(&side.side_num).is_none() {
+                self.sides.push(side);
+            }
+        }
+
+        self
+    }
 }
 
 pub fn get_timeular_config() -> TimeularConfig {
-    crate::config::get_config::<TimeularConfig>(CONFIG_KEY)
+    crate::config::get_config::<TimeularConfig>(CONFIG_KEY).add_missing_sides()
+}
+
+/// Reads and validates the config, for reloading it while running
+pub fn try_get_timeular_config() -> Result<TimeularConfig, String> {
+    if !check::report() {
+        return Err(String::from("see the problems above"));
+    }
+
+    crate::config::try_get_config::<TimeularConfig>(CONFIG_KEY)?
+        .map(TimeularConfig::add_missing_sides)
+        .ok_or(format!("[{}] is missing", CONFIG_KEY))
 }
 
 pub fn update_timeular_config(config: &TimeularConfig) {
     crate::config::update_config(CONFIG_KEY, config);
 }
+
+/// Labels a side the same way flipping to it during `--setup` does
+pub fn label_side(side_num: u8, label: String) -> Result<(), String> {
+    if !SIDES.contains(&side_num) {
+        return Err(format!(
+            "Side {} doesn't exist, the tracker has sides {} to {}",
+            side_num,
+            SIDES.start(),
+            SIDES.end()
+        ));
+    }
+
+    let mut config = get_timeular_config();
+    if config
+        .find_side(&side_num)
+        .is_some_and(|side| !side.configurable)
+    {
+        return Err(format!(
+            "Side {} is not configurable, set configurable = true for it in [{}] first",
+            side_num, CONFIG_KEY
+        ));
+    }
+
+    config.set_side(side_num, label);
+    update_timeular_config(&config);
+
+    Ok(())
+}
+
+pub fn print_sides() {
+    let config = get_timeular_config();
+
+    let mut message = String::from("Sides:");
+    for side_num in SIDES {
+        let label = match config.find_side(&side_num) {
+            Some(side) if !side.label.is_empty() => side.label.as_str(),
+            Some(side) if !side.configurable => "not configurable",
+            _ => "not labeled",
+        };
+        message.push_str(format!("\n{}: {}", side_num, label).as_str());
+    }
+    info!("{}", message);
+}
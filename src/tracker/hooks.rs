@@ -0,0 +1,254 @@
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, SecondsFormat};
+use log::{debug, error, warn};
+use serde::Serialize;
+use serde_json::Value;
+use simplelog::info;
+use tinytemplate::TinyTemplate;
+use tokio::process::Command;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::tracker::config::{Hooks, Side};
+
+/// Runs every batch of hooks in order, so the exit hooks of a side finish before the enter hooks
+/// of the next one start
+static WORKER: OnceLock<UnboundedSender<Batch>> = OnceLock::new();
+
+struct Batch {
+    commands: Vec<String>,
+    context: Context,
+    timeout: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct Context {
+    event: &'static str,
+    side_num: u8,
+    label: String,
+    start: String,
+    duration: i64,
+}
+
+impl Context {
+    fn envs(&self) -> [(&'static str, String); 5] {
+        [
+            ("TIMEULAR_EVENT", self.event.to_string()),
+            ("TIMEULAR_SIDE", self.side_num.to_string()),
+            ("TIMEULAR_LABEL", self.label.clone()),
+            ("TIMEULAR_START", self.start.clone()),
+            ("TIMEULAR_DURATION", self.duration.to_string()),
+        ]
+    }
+}
+
+pub fn on_enter(hooks: &Hooks, side: &Side, start: &DateTime<Local>) {
+    let context = Context {
+        event: "enter",
+        side_num: side.side_num,
+        label: side.label.clone(),
+        start: start.to_rfc3339_opts(SecondsFormat::Secs, true),
+        duration: 0,
+    };
+
+    run_hooks([&hooks.on_enter, &side.on_enter], context, hooks.timeout);
+}
+
+pub fn on_exit(hooks: &Hooks, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+    let context = Context {
+        event: "exit",
+        side_num: side.side_num,
+        label: side.label.clone(),
+        start: duration.0.to_rfc3339_opts(SecondsFormat::Secs, true),
+        duration: (duration.1 - duration.0).num_seconds(),
+    };
+
+    run_hooks([&hooks.on_exit, &side.on_exit], context, hooks.timeout);
+}
+
+fn run_hooks(templates: [&String; 2], context: Context, timeout: u64) {
+    let mut commands = Vec::new();
+    for template in templates.into_iter().filter(|t| !t.is_empty()) {
+        match render(template, &context) {
+            Ok(command) => commands.push(command),
+            Err(e) => error!("Could not render hook \"{}\": {}", template, e),
+        }
+    }
+
+    if commands.is_empty() {
+        return;
+    }
+
+    let batch = Batch {
+        commands,
+        context,
+        timeout,
+    };
+    if WORKER.get_or_init(spawn_worker).send(batch).is_err() {
+        error!("Could not run hooks, the hook worker stopped");
+    }
+}
+
+fn spawn_worker() -> UnboundedSender<Batch> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Batch>();
+
+    tokio::spawn(async move {
+        while let Some(batch) = receiver.recv().await {
+            for command in batch.commands {
+                run_command(&command, &batch.context, batch.timeout).await;
+            }
+        }
+    });
+
+    sender
+}
+
+/// Every value is quoted for the shell, so a label can't break out of the command
+fn render(template: &str, context: &Context) -> Result<String, tinytemplate::error::Error> {
+    let mut tt = TinyTemplate::new();
+    tt.set_default_formatter(&format_quoted);
+    tt.add_template("command", template)?;
+
+    tt.render("command", context)
+}
+
+fn format_quoted(value: &Value, output: &mut String) -> tinytemplate::error::Result<()> {
+    let value = match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    };
+    output.push_str(&shell_quote(&value));
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn shell_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\"").replace('%', "%%"))
+}
+
+#[cfg(not(windows))]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+async fn run_command(command: &str, context: &Context, timeout: u64) {
+    debug!("Running {} hook \"{}\"", context.event, command);
+
+    let mut cmd = shell_command(command);
+    cmd.envs(context.envs())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let output = match tokio::time::timeout(Duration::from_secs(timeout), cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            error!("Could not run hook \"{}\": {}", command, e);
+            return;
+        }
+        Err(_) => {
+            warn!("Hook \"{}\" timed out after {}s", command, timeout);
+            return;
+        }
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        info!("[hook] {}", line);
+    }
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        warn!("[hook] {}", line);
+    }
+
+    if !output.status.success() {
+        warn!("Hook \"{}\" exited with {}", command, output.status);
+    }
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(label: &str) -> Context {
+        Context {
+            event: "enter",
+            side_num: 3,
+            label: label.to_string(),
+            start: String::from("2024-05-06T09:00:00Z"),
+            duration: 0,
+        }
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn values_are_quoted() {
+        let command = render("notify-send {event} {side_num} {label}", &context("Focus")).unwrap();
+
+        assert_eq!(command, "notify-send 'enter' '3' 'Focus'");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn quotes_and_separators_stay_in_the_value() {
+        let command = render("echo {label}", &context("it's; rm -rf ~")).unwrap();
+
+        assert_eq!(command, "echo 'it'\\''s; rm -rf ~'");
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn shell_receives_the_label_as_one_argument() {
+        let command = render("printf %s {label}", &context("a'b; echo injected")).unwrap();
+
+        let output = shell_command(&command).output().await.unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "a'b; echo injected"
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn batches_run_one_after_another() {
+        let path = std::env::temp_dir().join(format!("timeular-hooks-{}", std::process::id()));
+        let file = shell_quote(&path.to_string_lossy());
+        let worker = spawn_worker();
+
+        for (event, delay) in [("exit", "0.2"), ("enter", "0")] {
+            let mut context = context("Focus");
+            context.event = event;
+            let batch = Batch {
+                commands: vec![format!("sleep {}; echo {} >> {}", delay, event, file)],
+                context,
+                timeout: 5,
+            };
+            assert!(worker.send(batch).is_ok());
+        }
+        drop(worker);
+        tokio::time::sleep(Duration::from_millis(600)).await;
+
+        let events = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(events, "exit\nenter\n");
+    }
+}
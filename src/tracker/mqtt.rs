@@ -0,0 +1,236 @@
+use std::fs;
+use std::process;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, SecondsFormat};
+use log::{debug, error};
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Outgoing, Packet, QoS, Transport};
+use serde_json::json;
+use simplelog::info;
+use tokio::task::JoinHandle;
+
+use crate::config::expand_path;
+use crate::tracker::config::Side;
+use crate::tracker::mqtt::config::{check_required, setup_config, try_create_config, MqttConfig};
+
+pub mod config;
+
+const IDLE: &str = "idle";
+/// How long closing waits for `offline` and the disconnect to reach the broker
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Publishes the tracker state next to the handler, enabled by the `[mqtt]` section
+pub struct Mqtt {
+    broker: Broker,
+    connection: JoinHandle<()>,
+}
+
+/// Shared with the connection task, which announces the reader again after every reconnect
+#[derive(Clone)]
+struct Broker {
+    client: AsyncClient,
+    config: MqttConfig,
+}
+
+impl Drop for Mqtt {
+    fn drop(&mut self) {
+        self.connection.abort();
+    }
+}
+
+impl Broker {
+    fn topic(&self, name: &str) -> String {
+        format!(
+            "{}/{}",
+            self.config.topic_prefix.trim_end_matches('/'),
+            name
+        )
+    }
+
+    /// Never waits for the broker, when it's unreachable and the queue is full the message is dropped
+    fn publish(&self, topic: String, retain: bool, payload: String) {
+        debug!("Publishing {} to {}", payload, topic);
+
+        if let Err(e) = self
+            .client
+            .try_publish(topic, QoS::AtLeastOnce, retain, payload)
+        {
+            error!("Could not publish MQTT message: {}", e);
+        }
+    }
+
+    /// The broker publishes the last will `offline` when the connection drops, so `online` is
+    /// published on every connect
+    fn announce(&self) {
+        self.publish(self.topic("availability"), true, String::from("online"));
+        if self.config.discovery {
+            self.publish_discovery();
+        }
+    }
+
+    fn publish_discovery(&self) {
+        let config = json!({
+            "name": "Timeular side",
+            "unique_id": format!("{}_current_side", self.config.client_id),
+            "state_topic": self.topic("current_side"),
+            "availability_topic": self.topic("availability"),
+            "icon": "mdi:cube-outline",
+            "device": {
+                "identifiers": [self.config.client_id],
+                "name": "Timeular Tracker",
+                "manufacturer": "Timeular",
+            },
+        });
+
+        self.publish(
+            format!(
+                "{}/sensor/{}/current_side/config",
+                self.config.discovery_prefix.trim_end_matches('/'),
+                self.config.client_id
+            ),
+            true,
+            config.to_string(),
+        );
+    }
+}
+
+impl Mqtt {
+    pub fn config(&self) -> &MqttConfig {
+        &self.broker.config
+    }
+
+    pub fn enter(&self, side: &Side) {
+        self.broker
+            .publish(self.broker.topic("current_side"), true, side.label.clone());
+    }
+
+    pub fn exit(&self, side: &Side, duration: &(DateTime<Local>, DateTime<Local>)) {
+        let interval = json!({
+            "side_num": side.side_num,
+            "label": side.label,
+            "start": duration.0.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "end": duration.1.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "duration": (duration.1 - duration.0).num_seconds(),
+        });
+
+        self.broker
+            .publish(self.broker.topic("interval"), false, interval.to_string());
+    }
+
+    /// The device is on a side that is not tracked
+    pub fn idle(&self) {
+        self.broker
+            .publish(self.broker.topic("current_side"), true, String::from(IDLE));
+    }
+
+    /// Publishes `offline` and disconnects cleanly, so the broker doesn't publish the last will
+    /// later, e.g. after a new connection already announced `online`
+    pub async fn close(mut self) {
+        self.broker.publish(
+            self.broker.topic("availability"),
+            true,
+            String::from("offline"),
+        );
+        if let Err(e) = self.broker.client.try_disconnect() {
+            error!("Could not disconnect from the MQTT broker: {}", e);
+            return;
+        }
+
+        if tokio::time::timeout(CLOSE_TIMEOUT, &mut self.connection)
+            .await
+            .is_err()
+        {
+            debug!("MQTT connection did not close in time");
+        }
+    }
+}
+
+fn create_options(config: &MqttConfig) -> Result<MqttOptions, String> {
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(
+        format!("{}/availability", config.topic_prefix.trim_end_matches('/')),
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+
+    if !config.username.is_empty() {
+        options.set_credentials(&config.username, &config.password);
+    }
+
+    if config.tls {
+        let transport = if config.ca_path.is_empty() {
+            Transport::tls_with_default_config()
+        } else {
+            let ca = fs::read(expand_path(&config.ca_path))
+                .map_err(|e| format!("Could not read mqtt.ca_path {}: {}", config.ca_path, e))?;
+            Transport::tls(ca, None, None)
+        };
+        options.set_transport(transport);
+    }
+
+    Ok(options)
+}
+
+/// Connects when the config has an `[mqtt]` section, missing settings are asked for like the
+/// handler's
+pub fn create_publisher(setup: bool) -> Option<Mqtt> {
+    let mut config = match try_create_config() {
+        Ok(config) => config?,
+        Err(e) => {
+            error!("Invalid config: {}", e);
+            process::exit(1);
+        }
+    };
+    setup_config(&mut config, setup);
+
+    match create_options(&config) {
+        Ok(options) => Some(connect(config, options)),
+        Err(e) => {
+            error!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Checks the `[mqtt]` section without asking for anything, `None` if there is none. Connecting
+/// with the result can't fail, so the current publisher can be closed first
+pub fn prepare() -> Result<Option<(MqttConfig, MqttOptions)>, String> {
+    let config = match try_create_config()? {
+        Some(config) => config,
+        None => return Ok(None),
+    };
+    check_required()?;
+    let options = create_options(&config)?;
+
+    Ok(Some((config, options)))
+}
+
+pub fn connect(config: MqttConfig, options: MqttOptions) -> Mqtt {
+    let (client, mut event_loop) = AsyncClient::new(options, 10);
+    let broker = Broker { client, config };
+
+    let announcer = broker.clone();
+    let connection = tokio::spawn(async move {
+        loop {
+            match event_loop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    info!("Connected to MQTT broker");
+                    announcer.announce();
+                }
+                Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    error!("MQTT connection error: {}", e);
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        }
+    });
+
+    let mqtt = Mqtt { broker, connection };
+    mqtt.idle();
+
+    mqtt
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use toml::Value;
+
+use crate::config::wizard::{self, Field};
+use crate::config::Config;
+
+pub const CONFIG_KEY: &str = "mqtt";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: String,
+    pub password: String,
+    pub tls: bool,
+    pub ca_path: String,
+    pub topic_prefix: String,
+    pub discovery: bool,
+    pub discovery_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        MqttConfig {
+            host: String::from("localhost"),
+            port: 1883,
+            client_id: String::from("timeular-reader"),
+            username: String::new(),
+            password: String::new(),
+            tls: false,
+            ca_path: String::new(),
+            topic_prefix: String::from("timeular"),
+            discovery: true,
+            discovery_prefix: String::from("homeassistant"),
+        }
+    }
+}
+impl<'de> Config<'de> for MqttConfig {
+    fn secret_fields() -> &'static [&'static str] {
+        &["password"]
+    }
+}
+
+/// `None` without an `[mqtt]` section, unlike handlers it's not added when it's missing
+pub fn try_create_config() -> Result<Option<MqttConfig>, String> {
+    crate::config::try_get_config::<MqttConfig>(CONFIG_KEY)
+}
+
+fn fields() -> Vec<Field> {
+    vec![
+        Field::text("host", "MQTT broker host"),
+        Field::number("port", "MQTT broker port").validate(|input| {
+            input
+                .parse::<u16>()
+                .map(|_| ())
+                .map_err(|_| String::from("Please provide a port between 1 and 65535"))
+        }),
+        Field::text(
+            "username",
+            "MQTT username, connects without credentials when it's not set",
+        ),
+        Field::text("password", "MQTT password")
+            .secret()
+            .required()
+            .only_if(|config| {
+                config
+                    .get("username")
+                    .and_then(Value::as_str)
+                    .is_some_and(|username| !username.is_empty())
+            }),
+    ]
+}
+
+pub fn setup_config(config: &mut MqttConfig, setup: bool) {
+    wizard::run(CONFIG_KEY, config, &fields(), setup);
+}
+
+pub fn check_required() -> Result<(), String> {
+    wizard::check::<MqttConfig>(CONFIG_KEY, &fields())
+}
+
+pub fn migrate_secrets() {
+    crate::config::migrate_secrets::<MqttConfig>(CONFIG_KEY);
+}
@@ -1,18 +1,21 @@
 use std::{error::Error, pin::Pin, sync::Arc};
 
-use crate::handler::{get_handler, Handlers};
+use crate::handler::{get_handler, try_get_handler, Handlers};
 use btleplug::api::Peripheral;
 use btleplug::api::{Central, ValueNotification};
 use btleplug::platform::{Adapter, PeripheralId};
 use chrono::{Local, TimeDelta};
 use futures::{Stream, StreamExt};
-use log::debug;
+use log::{debug, error};
 use simplelog::info;
 use strum::IntoEnumIterator;
 
-use crate::tracker::config::{Handler, Side};
+use crate::config::watcher::watch_config;
+use crate::config::wizard::{self, Field};
+use crate::tracker::config::{Handler, Side, TimeularConfig};
 
-use super::config;
+use super::mqtt::{self, Mqtt};
+use super::{config, hooks};
 
 pub async fn read_tracker(
     id: PeripheralId,
@@ -38,11 +41,7 @@ async fn setup_tracker_config(tracker: &impl Peripheral) {
 
     let mut config = config::get_timeular_config();
 
-    if !config.handler.is_empty() {
-        info!("Currently used handler: {}", config.handler);
-    }
-
-    let handler = get_handler_enum();
+    let handler = get_handler_enum(&config.handler);
     if handler.is_some() {
         config.handler = format!("{:?}", handler.unwrap()).to_string().to_lowercase();
     }
@@ -79,26 +78,20 @@ async fn setup_tracker_config(tracker: &impl Peripheral) {
     config::update_timeular_config(&config);
 }
 
-fn get_handler_enum() -> Option<Handlers> {
-    let mut message = String::from_utf8("Available handlers:".as_bytes().to_vec()).unwrap();
-
-    let mut i: u8 = 1;
-    for h in Handlers::iter() {
-        message.push_str(format!("\n{}: {:?}", i, h).to_lowercase().as_str());
-        i += 1;
-    }
-    info!("{message}\nChoose handler [1-{i}]:");
-
-    let mut handler = String::new();
-    std::io::stdin().read_line(&mut handler).unwrap();
-    handler = handler.trim().to_string();
-    if handler.is_empty() {
-        return None;
-    }
+/// Asks again until one of the handlers is chosen, `None` keeps the current one
+fn get_handler_enum(current: &String) -> Option<Handlers> {
+    let choices = Handlers::iter()
+        .map(|h| {
+            let name = format!("{:?}", h).to_lowercase();
+            (h as i64, name)
+        })
+        .collect();
+    let field = Field::choice("handler", "handler", choices);
+    let current = Handlers::try_from(current).ok().map(|h| h as i64);
 
-    let idx = handler.parse::<u8>().unwrap();
+    let idx = wizard::choose(config::CONFIG_KEY, &field, None, current)?;
 
-    Some(Handlers::try_from(idx).unwrap())
+    Handlers::try_from(idx as u8).ok()
 }
 
 async fn get_notification_stream(
@@ -120,45 +113,154 @@ async fn get_notification_stream(
 async fn read_orientation(tracker: &impl Peripheral, setup: bool) -> Result<(), Box<dyn Error>> {
     let mut notification_stream = get_notification_stream(tracker).await;
 
-    let config = config::get_timeular_config();
+    let mut config = config::get_timeular_config();
 
     debug!("Handler is: {}", config.handler);
-    let h: Box<dyn Handler> = get_handler(setup, &config).await;
+    let mut h: Box<dyn Handler> = get_handler(setup, &config).await;
+    let mut publisher = mqtt::create_publisher(setup);
+
+    let mut config_changes = watch_config();
+    let mut pending_config: Option<TimeularConfig> = None;
 
-    let mut prev_side: Option<&Side> = None;
+    let mut prev_side: Option<Side> = None;
     let mut start_date = Local::now();
 
     info!("Flip the device to the side you want to track");
-    while let Some(data) = notification_stream.next().await {
-        let side = config.get_side(&data.value[0]);
+    loop {
+        let data = tokio::select! {
+            data = notification_stream.next() => match data {
+                Some(data) => data,
+                None => break,
+            },
+            Ok(()) = config_changes.changed() => {
+                pending_config = match config::try_get_timeular_config() {
+                    Ok(new_config) => Some(new_config),
+                    Err(e) => {
+                        error!("Config changed but is invalid, keeping the current one: {}", e);
+                        None
+                    }
+                };
+
+                // Without a running interval there is nothing to finish first
+                match (&prev_side, pending_config.take()) {
+                    (None, Some(new_config)) => {
+                        reload(&mut config, &mut h, &mut publisher, new_config).await
+                    }
+                    (Some(_), Some(new_config)) => {
+                        info!("Config changed, it will be applied when you flip the device");
+                        pending_config = Some(new_config);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+        };
+
+        let side_num = data.value[0];
+        debug!("current side: {}, previous side: {:?}", side_num, prev_side);
+
+        let side_changed = prev_side.as_ref().map(|s| s.side_num) != Some(side_num);
+
+        if let Some(prev) = prev_side.take_if(|_| side_changed) {
+            let end_date = Local::now();
+            let duration = end_date - start_date;
 
-        if !side.label.is_empty() {
-            info!("Currently tracking {}", side.label);
-        }
+            log_time_spent(duration, &prev.label);
 
-        debug!("current side: {}, previous side: {:?}", side, prev_side);
+            hooks::on_exit(&config.hooks, &prev, &(start_date, end_date));
+            if let Some(publisher) = &publisher {
+                publisher.exit(&prev, &(start_date, end_date));
+            }
+            h.handle(&prev, &(start_date, end_date)).await;
 
-        if prev_side.is_some() && prev_side.unwrap() != side {
-            let end_date = Local::now();
-            let duration = end_date - start_date;
+            if let Some(new_config) = pending_config.take() {
+                reload(&mut config, &mut h, &mut publisher, new_config).await;
+            }
+        }
 
-            log_time_spent(duration, &prev_side.unwrap().label);
+        let side = config.get_side(&side_num).clone();
 
-            h.handle(prev_side.unwrap(), &(start_date, end_date)).await;
+        if !side.label.is_empty() {
+            info!("Currently tracking {}", side.label);
         }
 
         if !config.is_trackable(&side.side_num) {
+            if let Some(publisher) = publisher.as_ref().filter(|_| side_changed) {
+                publisher.idle();
+            }
             prev_side = None;
             continue;
         }
 
         start_date = Local::now();
+
+        if side_changed {
+            hooks::on_enter(&config.hooks, &side, &start_date);
+            if let Some(publisher) = &publisher {
+                publisher.enter(&side);
+            }
+            h.start(&side, &start_date).await;
+        }
+
         prev_side = Some(side);
     }
 
+    if let Some(publisher) = publisher {
+        publisher.close().await;
+    }
+
     return Ok(());
 }
 
+/// Swaps in the new config, its handler and the MQTT publisher, the old handler is dropped.
+/// Nothing is asked for, if the handler or the `[mqtt]` section can't be used everything is kept
+async fn reload(
+    config: &mut TimeularConfig,
+    h: &mut Box<dyn Handler>,
+    publisher: &mut Option<Mqtt>,
+    new_config: TimeularConfig,
+) {
+    let handler = match try_get_handler(&new_config).await {
+        Ok(handler) => handler,
+        Err(e) => {
+            error!(
+                "Config changed but the {} handler can't be used, keeping the current one: {}",
+                new_config.handler, e
+            );
+            return;
+        }
+    };
+    let prepared = match mqtt::prepare() {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            error!(
+                "Config changed but the mqtt section can't be used, keeping the current one: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    *config = new_config;
+    *h = handler;
+
+    let unchanged = match (&prepared, &*publisher) {
+        (Some((new, _)), Some(current)) => new == current.config(),
+        (None, None) => true,
+        _ => false,
+    };
+    if !unchanged {
+        // The old connection is closed first, so its last will can't follow the new `online`
+        if let Some(current) = publisher.take() {
+            current.close().await;
+        }
+        *publisher = prepared.map(|(config, options)| mqtt::connect(config, options));
+    }
+
+    info!("Config reloaded");
+    debug!("Handler is: {}", config.handler);
+}
+
 fn log_time_spent(duration: TimeDelta, label: &String) {
     let mut minutes = duration.num_minutes();
     if duration.num_minutes() > 0 && duration.num_hours() > 0 {